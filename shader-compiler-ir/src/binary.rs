@@ -0,0 +1,455 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! binary (de)serialization of IR, parallel to the `ToText`/`FromText` textual
+//! assembly grammar in [[`crate::text`]].
+//!
+//! Re-parsing the text grammar is the bottleneck for loading large shader IR,
+//! so this format skips lexing entirely: blocks and loops get dense `u32` ids
+//! assigned in definition order instead of interned `NamedId` names, and
+//! `BlockRef`/`LoopRef` encode as a back-reference to that id rather than a
+//! name lookup. The encoder and decoder otherwise mirror the scope/symbol
+//! bookkeeping `ToTextState`/`FromTextState` already do: `ToBinaryState`
+//! assigns each `BlockData`/`LoopData` its id the first time it's written
+//! (definition order), and `FromBinaryState` rebuilds the `IdRef`s and
+//! `OnceCell` bodies as it decodes, rejecting any id that was referenced
+//! before (or without ever) being defined.
+
+use crate::block::{BlockData, BlockRef, BreakBlock, ContinueLoop, Loop, LoopData, LoopHeader};
+use crate::prelude::*;
+use crate::Instruction;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// an error produced while decoding binary IR
+#[derive(Debug)]
+pub enum FromBinaryError {
+    /// the underlying reader failed
+    Io(io::Error),
+    /// the stream ended in the middle of a value
+    UnexpectedEof,
+    /// a tag byte didn't match any of the expected variants
+    InvalidTag {
+        /// the name of the type being decoded
+        type_name: &'static str,
+        /// the tag byte that was read
+        tag: u8,
+    },
+    /// a `BlockRef`/`LoopRef` referred to an id that was never defined, or
+    /// was defined later in the stream than the reference (forward
+    /// references aren't supported, matching the text grammar's
+    /// define-before-use rule)
+    DanglingReference {
+        /// `"block"` or `"loop"`
+        kind: &'static str,
+        /// the id that didn't resolve
+        id: u32,
+    },
+}
+
+impl From<io::Error> for FromBinaryError {
+    fn from(error: io::Error) -> Self {
+        FromBinaryError::Io(error)
+    }
+}
+
+impl fmt::Display for FromBinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FromBinaryError::Io(error) => write!(f, "I/O error: {}", error),
+            FromBinaryError::UnexpectedEof => write!(f, "unexpected end of binary IR stream"),
+            FromBinaryError::InvalidTag { type_name, tag } => {
+                write!(f, "invalid tag byte {} while decoding {}", tag, type_name)
+            }
+            FromBinaryError::DanglingReference { kind, id } => {
+                write!(f, "dangling {} reference: id {} not yet defined", kind, id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FromBinaryError {}
+
+/// state struct for `ToBinary`, tracking the dense ids assigned to blocks and
+/// loops in the order they're first written
+pub struct ToBinaryState<'g, 'w> {
+    writer: &'w mut dyn Write,
+    block_ids: HashMap<IdRef<'g, BlockData<'g>>, u32>,
+    loop_ids: HashMap<IdRef<'g, LoopData<'g>>, u32>,
+}
+
+impl<'g, 'w> ToBinaryState<'g, 'w> {
+    /// create a new `ToBinaryState` writing to `writer`
+    pub fn new(writer: &'w mut dyn Write) -> Self {
+        Self {
+            writer,
+            block_ids: HashMap::new(),
+            loop_ids: HashMap::new(),
+        }
+    }
+    /// write a single byte
+    pub fn write_u8(&mut self, value: u8) -> io::Result<()> {
+        self.writer.write_all(&[value])
+    }
+    /// write a little-endian `u32`
+    pub fn write_u32(&mut self, value: u32) -> io::Result<()> {
+        self.writer.write_all(&value.to_le_bytes())
+    }
+    /// write a length-prefixed byte string
+    pub fn write_bytes(&mut self, value: &[u8]) -> io::Result<()> {
+        self.write_u32(value.len() as u32)?;
+        self.writer.write_all(value)
+    }
+    /// write a length-prefixed UTF-8 string
+    pub fn write_str(&mut self, value: &str) -> io::Result<()> {
+        self.write_bytes(value.as_bytes())
+    }
+    /// get the id for `block`, assigning it the next dense id if this is the
+    /// first time it's been written. The returned `bool` is `true` the first
+    /// time, so the caller knows whether it still needs to write the definition.
+    pub(crate) fn get_or_assign_block_id(&mut self, block: IdRef<'g, BlockData<'g>>) -> (u32, bool) {
+        let next_id = self.block_ids.len() as u32;
+        match self.block_ids.get(&block) {
+            Some(&id) => (id, false),
+            None => {
+                self.block_ids.insert(block, next_id);
+                (next_id, true)
+            }
+        }
+    }
+    /// get the id for `loop_`, assigning it the next dense id if this is the
+    /// first time it's been written
+    pub(crate) fn get_or_assign_loop_id(&mut self, loop_: IdRef<'g, LoopData<'g>>) -> (u32, bool) {
+        let next_id = self.loop_ids.len() as u32;
+        match self.loop_ids.get(&loop_) {
+            Some(&id) => (id, false),
+            None => {
+                self.loop_ids.insert(loop_, next_id);
+                (next_id, true)
+            }
+        }
+    }
+}
+
+/// state struct for `FromBinary`, rebuilding `IdRef`s as blocks and loops are
+/// decoded and rejecting references to ids that aren't yet defined
+pub struct FromBinaryState<'g, 'r> {
+    reader: &'r mut dyn Read,
+    global_state: &'g GlobalState<'g>,
+    blocks: Vec<Option<IdRef<'g, BlockData<'g>>>>,
+    loops: Vec<Option<IdRef<'g, LoopData<'g>>>>,
+}
+
+impl<'g, 'r> FromBinaryState<'g, 'r> {
+    /// create a new `FromBinaryState` reading from `reader`
+    pub fn new(reader: &'r mut dyn Read, global_state: &'g GlobalState<'g>) -> Self {
+        Self {
+            reader,
+            global_state,
+            blocks: Vec::new(),
+            loops: Vec::new(),
+        }
+    }
+    /// get the `GlobalState` reference
+    pub fn global_state(&self) -> &'g GlobalState<'g> {
+        self.global_state
+    }
+    /// read a single byte
+    pub fn read_u8(&mut self) -> Result<u8, FromBinaryError> {
+        let mut buf = [0u8; 1];
+        self.reader
+            .read_exact(&mut buf)
+            .map_err(|_| FromBinaryError::UnexpectedEof)?;
+        Ok(buf[0])
+    }
+    /// read a little-endian `u32`
+    pub fn read_u32(&mut self) -> Result<u32, FromBinaryError> {
+        let mut buf = [0u8; 4];
+        self.reader
+            .read_exact(&mut buf)
+            .map_err(|_| FromBinaryError::UnexpectedEof)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+    /// read a length-prefixed byte string
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, FromBinaryError> {
+        let len = self.read_u32()? as usize;
+        let mut buf = vec![0u8; len];
+        self.reader
+            .read_exact(&mut buf)
+            .map_err(|_| FromBinaryError::UnexpectedEof)?;
+        Ok(buf)
+    }
+    /// read a length-prefixed UTF-8 string
+    pub fn read_str(&mut self) -> Result<String, FromBinaryError> {
+        let bytes = self.read_bytes()?;
+        String::from_utf8(bytes).map_err(|_| FromBinaryError::InvalidTag {
+            type_name: "str",
+            tag: 0,
+        })
+    }
+    /// allocate a slot for a new block definition with the given dense id,
+    /// filling in its `IdRef` once `BlockData` has been allocated
+    pub(crate) fn define_block(&mut self, id: u32, value: IdRef<'g, BlockData<'g>>) {
+        let id = id as usize;
+        if self.blocks.len() <= id {
+            self.blocks.resize(id + 1, None);
+        }
+        self.blocks[id] = Some(value);
+    }
+    /// allocate a slot for a new loop definition with the given dense id
+    pub(crate) fn define_loop(&mut self, id: u32, value: IdRef<'g, LoopData<'g>>) {
+        let id = id as usize;
+        if self.loops.len() <= id {
+            self.loops.resize(id + 1, None);
+        }
+        self.loops[id] = Some(value);
+    }
+    /// resolve a block back-reference, erroring if `id` hasn't been defined yet
+    pub(crate) fn get_block(&self, id: u32) -> Result<IdRef<'g, BlockData<'g>>, FromBinaryError> {
+        self.blocks
+            .get(id as usize)
+            .copied()
+            .flatten()
+            .ok_or(FromBinaryError::DanglingReference { kind: "block", id })
+    }
+    /// resolve a loop back-reference, erroring if `id` hasn't been defined yet
+    pub(crate) fn get_loop(&self, id: u32) -> Result<IdRef<'g, LoopData<'g>>, FromBinaryError> {
+        self.loops
+            .get(id as usize)
+            .copied()
+            .flatten()
+            .ok_or(FromBinaryError::DanglingReference { kind: "loop", id })
+    }
+}
+
+/// convert IR to its binary representation. Mirrors `ToText`.
+pub trait ToBinary<'g> {
+    /// write `self` to `state`
+    fn to_binary(&self, state: &mut ToBinaryState<'g, '_>) -> io::Result<()>;
+}
+
+/// parse IR from its binary representation. Mirrors `FromText`.
+pub trait FromBinary<'g>: Sized {
+    /// the type produced by decoding successfully
+    type Parsed;
+    /// top-level decode function
+    fn parse_binary(
+        reader: &mut dyn Read,
+        global_state: &'g GlobalState<'g>,
+    ) -> Result<Self::Parsed, FromBinaryError> {
+        let mut state = FromBinaryState::new(reader, global_state);
+        Self::from_binary(&mut state)
+    }
+    /// do the actual decoding work
+    fn from_binary(state: &mut FromBinaryState<'g, '_>) -> Result<Self::Parsed, FromBinaryError>;
+}
+
+impl<'g, T: ToBinary<'g>> ToBinary<'g> for Inhabitable<Vec<T>> {
+    fn to_binary(&self, state: &mut ToBinaryState<'g, '_>) -> io::Result<()> {
+        match self {
+            Uninhabited => state.write_u8(0),
+            Inhabited(elements) => {
+                state.write_u8(1)?;
+                state.write_u32(elements.len() as u32)?;
+                for element in elements {
+                    element.to_binary(state)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<'g, T: FromBinary<'g, Parsed = T>> FromBinary<'g> for Inhabitable<Vec<T>> {
+    type Parsed = Self;
+    fn from_binary(state: &mut FromBinaryState<'g, '_>) -> Result<Self, FromBinaryError> {
+        match state.read_u8()? {
+            0 => Ok(Uninhabited),
+            1 => {
+                let len = state.read_u32()? as usize;
+                let mut elements = Vec::with_capacity(len);
+                for _ in 0..len {
+                    elements.push(T::from_binary(state)?);
+                }
+                Ok(Inhabited(elements))
+            }
+            tag => Err(FromBinaryError::InvalidTag {
+                type_name: "Inhabitable<Vec<T>>",
+                tag,
+            }),
+        }
+    }
+}
+
+impl<'g, T: ToBinary<'g>> ToBinary<'g> for Vec<T> {
+    fn to_binary(&self, state: &mut ToBinaryState<'g, '_>) -> io::Result<()> {
+        state.write_u32(self.len() as u32)?;
+        for element in self {
+            element.to_binary(state)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'g, T: FromBinary<'g, Parsed = T>> FromBinary<'g> for Vec<T> {
+    type Parsed = Self;
+    fn from_binary(state: &mut FromBinaryState<'g, '_>) -> Result<Self, FromBinaryError> {
+        let len = state.read_u32()? as usize;
+        let mut retval = Vec::with_capacity(len);
+        for _ in 0..len {
+            retval.push(T::from_binary(state)?);
+        }
+        Ok(retval)
+    }
+}
+
+impl<'g> ToBinary<'g> for BlockRef<'g> {
+    fn to_binary(&self, state: &mut ToBinaryState<'g, '_>) -> io::Result<()> {
+        let (id, _is_new) = state.get_or_assign_block_id(self.value());
+        state.write_u32(id)
+    }
+}
+
+impl<'g> FromBinary<'g> for BlockRef<'g> {
+    type Parsed = Self;
+    fn from_binary(state: &mut FromBinaryState<'g, '_>) -> Result<Self, FromBinaryError> {
+        let id = state.read_u32()?;
+        Ok(BlockRef::new(state.get_block(id)?))
+    }
+}
+
+impl<'g> ToBinary<'g> for Block<'g> {
+    fn to_binary(&self, state: &mut ToBinaryState<'g, '_>) -> io::Result<()> {
+        let (id, is_new) = state.get_or_assign_block_id(self.value());
+        state.write_u32(id)?;
+        assert!(
+            is_new,
+            "block instruction must be written before any reference to it"
+        );
+        let BlockData {
+            name,
+            body,
+            result_definitions,
+        } = &***self;
+        state.write_str(name)?;
+        result_definitions.to_binary(state)?;
+        let body = body.get().expect("block body not set");
+        body.to_binary(state)
+    }
+}
+
+impl<'g> FromBinary<'g> for Block<'g> {
+    type Parsed = Self;
+    fn from_binary(state: &mut FromBinaryState<'g, '_>) -> Result<Self, FromBinaryError> {
+        let id = state.read_u32()?;
+        let name = state.read_str()?;
+        let result_definitions = Inhabitable::<Vec<ValueDefinition>>::from_binary(state)?;
+        let block = Block::without_body(&*name, result_definitions, state.global_state());
+        state.define_block(id, block.value());
+        let body = Vec::<Instruction>::from_binary(state)?;
+        block.value().set_body(body);
+        Ok(block)
+    }
+}
+
+impl<'g> ToBinary<'g> for LoopHeader<'g> {
+    fn to_binary(&self, state: &mut ToBinaryState<'g, '_>) -> io::Result<()> {
+        self.argument_definitions.to_binary(state)
+    }
+}
+
+impl<'g> FromBinary<'g> for LoopHeader<'g> {
+    type Parsed = Self;
+    fn from_binary(state: &mut FromBinaryState<'g, '_>) -> Result<Self, FromBinaryError> {
+        Ok(LoopHeader {
+            argument_definitions: Vec::<ValueDefinition>::from_binary(state)?,
+        })
+    }
+}
+
+impl<'g> ToBinary<'g> for Loop<'g> {
+    fn to_binary(&self, state: &mut ToBinaryState<'g, '_>) -> io::Result<()> {
+        let (id, is_new) = state.get_or_assign_loop_id(self.value());
+        state.write_u32(id)?;
+        assert!(
+            is_new,
+            "loop instruction must be written before any reference to it"
+        );
+        let LoopData {
+            name,
+            arguments,
+            header,
+            body,
+        } = &***self;
+        state.write_str(name)?;
+        arguments.to_binary(state)?;
+        header.to_binary(state)?;
+        body.to_binary(state)
+    }
+}
+
+impl<'g> FromBinary<'g> for Loop<'g> {
+    type Parsed = Self;
+    fn from_binary(state: &mut FromBinaryState<'g, '_>) -> Result<Self, FromBinaryError> {
+        let id = state.read_u32()?;
+        let name = state.read_str()?;
+        let arguments = Vec::<ValueUse>::from_binary(state)?;
+        let header = LoopHeader::from_binary(state)?;
+        // the body `Block` is decoded inline rather than through
+        // `Block::from_binary` so the loop is defined before the body's
+        // instructions, which may `continue` it, are decoded
+        let body_id = state.read_u32()?;
+        let body_name = state.read_str()?;
+        let body_result_definitions = Inhabitable::<Vec<ValueDefinition>>::from_binary(state)?;
+        let body = Block::without_body(&*body_name, body_result_definitions, state.global_state());
+        state.define_block(body_id, body.value());
+        let loop_ = Loop::new(
+            &*name,
+            arguments,
+            header.argument_definitions,
+            body,
+            state.global_state(),
+        );
+        state.define_loop(id, loop_.value());
+        let body_instructions = Vec::<Instruction>::from_binary(state)?;
+        loop_.body.value().set_body(body_instructions);
+        Ok(loop_)
+    }
+}
+
+impl<'g> ToBinary<'g> for BreakBlock<'g> {
+    fn to_binary(&self, state: &mut ToBinaryState<'g, '_>) -> io::Result<()> {
+        self.block.to_binary(state)?;
+        self.block_results.to_binary(state)
+    }
+}
+
+impl<'g> FromBinary<'g> for BreakBlock<'g> {
+    type Parsed = Self;
+    fn from_binary(state: &mut FromBinaryState<'g, '_>) -> Result<Self, FromBinaryError> {
+        Ok(BreakBlock {
+            block: BlockRef::from_binary(state)?,
+            block_results: Vec::<ValueUse>::from_binary(state)?,
+        })
+    }
+}
+
+impl<'g> ToBinary<'g> for ContinueLoop<'g> {
+    fn to_binary(&self, state: &mut ToBinaryState<'g, '_>) -> io::Result<()> {
+        let (id, _is_new) = state.get_or_assign_loop_id(self.target_loop.value());
+        state.write_u32(id)?;
+        self.loop_arguments.to_binary(state)
+    }
+}
+
+impl<'g> FromBinary<'g> for ContinueLoop<'g> {
+    type Parsed = Self;
+    fn from_binary(state: &mut FromBinaryState<'g, '_>) -> Result<Self, FromBinaryError> {
+        let id = state.read_u32()?;
+        Ok(ContinueLoop {
+            target_loop: crate::block::LoopRef::new(state.get_loop(id)?),
+            loop_arguments: Vec::<ValueUse>::from_binary(state)?,
+        })
+    }
+}
@@ -2,14 +2,18 @@
 // See Notices.txt for copyright information
 
 use crate::prelude::*;
+use crate::text::CommentTrivia;
+use crate::text::ErrorByteRange;
 use crate::text::FromTextError;
 use crate::text::FromTextState;
 use crate::text::FromTextSymbol;
 use crate::text::FromTextSymbolsState;
 use crate::text::FromTextSymbolsStateBase;
+use crate::text::Keyword;
 use crate::text::NamedId;
 use crate::text::NewOrOld;
 use crate::text::Punctuation;
+use crate::text::RecoveryBoundary;
 use crate::text::ToTextState;
 use crate::text::Token;
 use crate::text::TokenKind;
@@ -38,7 +42,13 @@ impl<'g> ToText<'g> for BreakBlock<'g> {
             block_results,
         } = self;
         block.to_text(state)?;
-        block_results.to_text(state)
+        block_results.to_text(state)?;
+        if state.is_verbose() {
+            if let Some(distance) = state.block_break_distance(block.value()) {
+                state.write_verbose_comment(format_args!("breaks {} scope(s)", distance))?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -202,6 +212,7 @@ impl<'g> Block<'g> {
         after_result_definitions_callback: AfterResultDefinitionsCallback,
         before_body_callback: BeforeBodyCallback,
     ) -> Result<IdRef<'g, BlockData<'g>>, FromTextError> {
+        let leading_comments = state.take_leading_comments();
         let kind_location = state.peek_token()?.span;
         if Self::KIND != InstructionKind::from_text(state)? {
             state.error_at(
@@ -220,20 +231,22 @@ impl<'g> Block<'g> {
         state.scope_stack_top = initial_scope;
         let scope = state.push_new_nested_scope();
         let block = Block::without_body(name.name, result_definitions, state.global_state());
-        if state
-            .insert_symbol(
-                name,
-                FromTextSymbol {
-                    value: block.value(),
-                    scope,
-                },
-            )
-            .is_err()
-        {
-            state.error_at(name_location, "duplicate block name")?;
+        if let Err(previous) = state.insert_symbol(
+            name,
+            FromTextSymbol {
+                value: block.value(),
+                scope,
+                definition_span: name_location.error_byte_range(),
+            },
+        ) {
+            state.error_at_diagnostic(
+                state
+                    .diagnostic(name_location, "duplicate block name")
+                    .with_secondary(previous.definition_span, "name first defined here"),
+            )?;
         }
         let missing_closing_brace = "missing closing curly brace: '}'";
-        state.parse_parenthesized(
+        let retval = state.parse_parenthesized(
             Punctuation::LCurlyBrace,
             "missing opening curly brace: '{'",
             Punctuation::RCurlyBrace,
@@ -255,7 +268,14 @@ impl<'g> Block<'g> {
                         TokenKind::Punct(Punctuation::RCurlyBrace) => break,
                         _ => {}
                     }
-                    let instruction = Instruction::from_text(state)?;
+                    let instruction = match Instruction::from_text(state) {
+                        Ok(instruction) => instruction,
+                        Err(_) if state.is_recovering_errors() => {
+                            state.recover_to_safe_boundary(RecoveryBoundary::StatementEnd);
+                            continue;
+                        }
+                        Err(error) => return Err(error),
+                    };
                     state.parse_punct_token_or_error(
                         Punctuation::Semicolon,
                         "missing terminating semicolon: ';'",
@@ -274,7 +294,16 @@ impl<'g> Block<'g> {
                 state.scope_stack_top = results_scope;
                 Ok(block_data)
             },
-        )
+        )?;
+        let trailing_comment = state.take_trailing_comment_same_line();
+        state.set_block_comment_trivia(
+            retval,
+            CommentTrivia {
+                leading: leading_comments,
+                trailing: trailing_comment,
+            },
+        );
+        Ok(retval)
     }
 }
 
@@ -306,7 +335,7 @@ impl<'g> FromText<'g> for BlockRef<'g> {
     fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
         let name_location = state.peek_token()?.span;
         let name = NamedId::from_text(state)?;
-        if let Some(FromTextSymbol { value, scope }) = state.get_symbol(name) {
+        if let Some(FromTextSymbol { value, scope, .. }) = state.get_symbol(name) {
             if state.is_scope_visible(scope) {
                 Ok(BlockRef::new(value))
             } else {
@@ -329,6 +358,36 @@ impl<'g> ToText<'g> for BlockRef<'g> {
 
 impl<'g> FromText<'g> for Block<'g> {
     type Parsed = Self;
+    fn parse(
+        file_name: impl std::borrow::Borrow<str>,
+        text: impl std::borrow::Borrow<str>,
+        global_state: &'g GlobalState<'g>,
+    ) -> Result<Self, FromTextError> {
+        let file_name = file_name.borrow();
+        let text = text.borrow();
+        let source_code = crate::text::FromTextSourceCode::new(file_name, text);
+        let mut state = FromTextState::new(&source_code, global_state);
+        let retval = Self::from_text(&mut state)?;
+        if !state.peek_token()?.kind.is_end_of_file() {
+            state.error_at_peek_token("extra tokens at end")?;
+        }
+        // `parse` is the one entry point guaranteed to see a complete,
+        // self-contained tree (`from_text` is also used while parsing a
+        // block nested inside an enclosing one, where break/continue targets
+        // outside the subtree being parsed would spuriously fail
+        // verification), so this is where `crate::verify::verify` runs.
+        if cfg!(debug_assertions) {
+            if let Err(errors) = crate::verify::verify(&retval) {
+                let message = errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                state.error_at(state.location, message)?;
+            }
+        }
+        Ok(retval)
+    }
     fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
         let mut retval = None;
         Self::from_text_with_callbacks(
@@ -359,10 +418,16 @@ impl<'g> ToText<'g> for Block<'g> {
         } = &***self;
         result_definitions.to_text(state)?;
         writeln!(state, " {{")?;
+        state.record_block_depth(self.value());
         state.indent(|state| -> fmt::Result {
-            for instruction in body.get().expect("block body not set") {
+            let body = body.get().expect("block body not set");
+            for (index, instruction) in body.iter().enumerate() {
                 instruction.to_text(state)?;
-                writeln!(state, ";")?;
+                write!(state, ";")?;
+                if state.is_verbose() && index + 1 == body.len() {
+                    state.write_verbose_comment(format_args!("terminator"))?;
+                }
+                writeln!(state)?;
             }
             Ok(())
         })?;
@@ -473,7 +538,7 @@ impl<'g> FromText<'g> for LoopRef<'g> {
     fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
         let name_location = state.peek_token()?.span;
         let name = NamedId::from_text(state)?;
-        if let Some(FromTextSymbol { value, scope }) = state.get_symbol(name) {
+        if let Some(FromTextSymbol { value, scope, .. }) = state.get_symbol(name) {
             if state.is_scope_visible(scope) {
                 Ok(LoopRef::new(value))
             } else {
@@ -497,6 +562,7 @@ impl<'g> ToText<'g> for LoopRef<'g> {
 impl<'g> FromText<'g> for Loop<'g> {
     type Parsed = Self;
     fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
+        let leading_comments = state.take_leading_comments();
         let kind_location = state.peek_token()?.span;
         if Self::KIND != InstructionKind::from_text(state)? {
             state.error_at(
@@ -508,7 +574,7 @@ impl<'g> FromText<'g> for Loop<'g> {
         let name = NamedId::from_text(state)?;
         let arguments = Vec::<ValueUse>::from_text(state)?;
         let missing_closing_brace = "missing closing curly brace: '}'";
-        state.parse_parenthesized(
+        let loop_ = state.parse_parenthesized(
             Punctuation::LCurlyBrace,
             "missing opening curly brace: '{'",
             Punctuation::RCurlyBrace,
@@ -542,17 +608,22 @@ impl<'g> FromText<'g> for Loop<'g> {
                             block,
                             state.global_state(),
                         );
-                        if state
-                            .insert_symbol(
-                                name,
-                                FromTextSymbol {
-                                    value: loop_.value(),
-                                    scope,
-                                },
-                            )
-                            .is_err()
-                        {
-                            state.error_at(name_location, "duplicate loop name")?;
+                        if let Err(previous) = state.insert_symbol(
+                            name,
+                            FromTextSymbol {
+                                value: loop_.value(),
+                                scope,
+                                definition_span: name_location.error_byte_range(),
+                            },
+                        ) {
+                            state.error_at_diagnostic(
+                                state
+                                    .diagnostic(name_location, "duplicate loop name")
+                                    .with_secondary(
+                                        previous.definition_span,
+                                        "name first defined here",
+                                    ),
+                            )?;
                         }
                         retval = Some(loop_);
                         Ok(())
@@ -561,7 +632,16 @@ impl<'g> FromText<'g> for Loop<'g> {
                 state.scope_stack_top = results_scope.expect("known to be Some");
                 Ok(retval.expect("known to be Some"))
             },
-        )
+        )?;
+        let trailing_comment = state.take_trailing_comment_same_line();
+        state.set_loop_comment_trivia(
+            loop_.value(),
+            CommentTrivia {
+                leading: leading_comments,
+                trailing: trailing_comment,
+            },
+        );
+        Ok(loop_)
     }
 }
 
@@ -583,6 +663,7 @@ impl<'g> ToText<'g> for Loop<'g> {
         write!(state, " ")?;
         arguments.to_text(state)?;
         writeln!(state, " {{")?;
+        state.record_loop_depth(self.value());
         state.indent(|state| {
             write!(state, "-> ")?;
             argument_definitions.to_text(state)?;
@@ -614,7 +695,13 @@ impl<'g> ToText<'g> for ContinueLoop<'g> {
             loop_arguments,
         } = self;
         target_loop.to_text(state)?;
-        loop_arguments.to_text(state)
+        loop_arguments.to_text(state)?;
+        if state.is_verbose() {
+            if let Some(distance) = state.loop_continue_distance(target_loop.value()) {
+                state.write_verbose_comment(format_args!("continues {} scope(s) back", distance))?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -646,6 +733,113 @@ impl<'g> CodeIO<'g> for ContinueLoop<'g> {
     }
 }
 
+/// the data owned by a `Global`
+#[derive(Eq, PartialEq, Hash, Debug)]
+pub struct GlobalData<'g> {
+    /// the name of the `Global` -- doesn't need to be unique
+    pub name: Interned<'g, str>,
+    /// the type of the global's storage
+    pub global_type: Interned<'g, Type<'g>>,
+    /// the global's initial value, or `None` if it starts out uninitialized
+    pub initializer: Option<Interned<'g, Const<'g>>>,
+}
+
+/// a module-scoped global variable.
+///
+/// unlike a `ValueDefinition`, a `Global`'s storage outlives any single block, so front-ends
+/// can lower shader uniforms, buffers, and shared memory into globals instead of faking that
+/// storage as loop arguments.
+///
+/// this is a standalone building block: it is not yet a `Module` member nor an operand of any
+/// `Load`/`Store` instruction, since neither exists in this part of the tree yet.
+#[derive(Eq, PartialEq, Hash, Debug)]
+pub struct Global<'g> {
+    value: IdRef<'g, GlobalData<'g>>,
+}
+
+impl<'g> Global<'g> {
+    /// create a new global variable
+    pub fn new<Initializer: Internable<'g, Interned = Const<'g>>>(
+        name: impl Internable<'g, Interned = str>,
+        global_type: impl Internable<'g, Interned = Type<'g>>,
+        initializer: Option<Initializer>,
+        global_state: &'g GlobalState<'g>,
+    ) -> Self {
+        Global {
+            value: global_state.alloc(GlobalData {
+                name: name.intern(global_state),
+                global_type: global_type.intern(global_state),
+                initializer: initializer.map(|v| v.intern(global_state)),
+            }),
+        }
+    }
+    /// get the contained `IdRef<GlobalData>`
+    pub fn value(&self) -> IdRef<'g, GlobalData<'g>> {
+        self.value
+    }
+}
+
+impl<'g> Deref for Global<'g> {
+    type Target = IdRef<'g, GlobalData<'g>>;
+    fn deref(&self) -> &IdRef<'g, GlobalData<'g>> {
+        &self.value
+    }
+}
+
+impl<'g> FromText<'g> for Global<'g> {
+    type Parsed = Self;
+    fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
+        state.parse_keyword_token_or_error(Keyword::Variable, "missing 'variable'")?;
+        let name = match state.peek_token()?.kind {
+            TokenKind::Identifier(name) => {
+                state.parse_token()?;
+                name
+            }
+            _ => state
+                .error_at_peek_token("missing global variable name")?
+                .into(),
+        };
+        state.parse_punct_token_or_error(
+            Punctuation::Colon,
+            "missing colon (':') after global variable name",
+        )?;
+        let global_type = Type::from_text(state)?;
+        let initializer = if state.peek_token()?.kind.punct() == Some(Punctuation::Equal) {
+            state.parse_token()?;
+            Some(Const::from_text(state)?)
+        } else {
+            None
+        };
+        state.parse_punct_token_or_error(
+            Punctuation::Semicolon,
+            "missing semicolon (';') after global variable declaration",
+        )?;
+        Ok(Global::new(
+            name,
+            global_type,
+            initializer,
+            state.global_state(),
+        ))
+    }
+}
+
+impl<'g> ToText<'g> for Global<'g> {
+    fn to_text(&self, state: &mut ToTextState<'g, '_>) -> fmt::Result {
+        let GlobalData {
+            name,
+            global_type,
+            initializer,
+        } = &***self;
+        write!(state, "{} {}: ", Keyword::Variable, &**name)?;
+        global_type.to_text(state)?;
+        if let Some(initializer) = initializer {
+            write!(state, " = ")?;
+            initializer.to_text(state)?;
+        }
+        write!(state, ";")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -738,5 +932,49 @@ mod tests {
             Block,
             block1
         );
+
+        // a `BreakBlock` nested inside a `Loop` targeting the block lexically enclosing that
+        // loop, demonstrating that such back-edges round-trip as a plain label reference rather
+        // than requiring the target block's body to be inlined at the use site.
+        let block1 = Block::without_body("block1", Uninhabited, global_state);
+        let mut block1_body = Vec::new();
+        let block2 = Block::without_body("block2", Uninhabited, global_state);
+        let mut block2_body = Vec::new();
+        let loop1 = Loop::new("loop1", vec![], vec![], block2, global_state);
+        block2_body.push(Instruction::without_location(BreakBlock {
+            block: BlockRef::new(block1.value()),
+            block_results: vec![],
+        }));
+        loop1.body.set_body(block2_body);
+        block1_body.push(Instruction::without_location(loop1));
+        block1.set_body(block1_body);
+        test_from_to_text!(
+            global_state,
+            concat!(
+                "block block1 -> ! {\n",
+                "    loop loop1 [] {\n",
+                "        -> [];\n",
+                "        block block2 -> ! {\n",
+                "            break block1[];\n",
+                "        }\n",
+                "    };\n",
+                "}"
+            ),
+            Block,
+            block1
+        );
+
+        test_from_to_text!(
+            global_state,
+            "variable v1: i32;",
+            Global,
+            Global::new::<Interned<'_, Const<'_>>>("v1", IntegerType::Int32, None, global_state)
+        );
+        test_from_to_text!(
+            global_state,
+            "variable v2: i32 = 0x1i32;",
+            Global,
+            Global::new("v2", IntegerType::Int32, Some(1u32), global_state)
+        );
     }
 }
\ No newline at end of file
@@ -0,0 +1,586 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! constant folding for `Const`
+//!
+//! front-ends often build up constant expressions (e.g. computing an array
+//! stride at compile time) that would otherwise have to be emitted as
+//! runtime ALU instructions operating on `undef`-free constant operands.
+//! `fold_binary`/`fold_unary` evaluate [[`crate::consts::Const`]] directly
+//! instead, returning a freshly interned `Const` or a [`ConstEvalError`]
+//! when the operation has no defined meaning for the operand type(s).
+//!
+//! integer ops are bit-precise: `self` is carried in a `u128` accumulator,
+//! signed ops run in `i128` (via [`sext`]), unsigned ops run directly in
+//! `u128`, and the result is [`clip`]ped back to the operand's bit width
+//! before being repackaged into the matching `ConstInteger` variant.
+//! `RelaxedInt32`/`RelaxedFloat32` fold the same as their non-relaxed
+//! 32-bit counterparts but preserve the relaxed variant in the result.
+//!
+//! `Array`/`Struct` fold element-/field-wise, same as `Vector` already did;
+//! `ScalableVector` folds its one repeated element once, since every lane
+//! (however many `vscale` turns out to mean at run time) would compute the
+//! same result.
+//!
+//! # the worklist pass is not implemented
+//!
+//! the original intent here was a worklist pass seeded from every `Value`
+//! whose result is already constant, evaluating each downstream ALU/branch
+//! instruction against `fold_binary`/`fold_unary` and re-queueing *its*
+//! users, so e.g. `loop_counter + loop_increment` folds as soon as both
+//! operands become known, however many instructions apart they are. That
+//! pass does not exist: it would have to walk a function body the same way
+//! `dce.rs`'s `LiveTargets::scan_body` walks one, downcasting each
+//! `Instruction` to find the ones worth folding, but no arithmetic or
+//! branch instruction in this crate is wired into that downcast system --
+//! `BreakBlock`/`ContinueLoop`/`LoopHeader`/`Loop`/`LoopData`/`Block`/
+//! `BlockData` (`block.rs`) are the only [[`crate::CodeIO`]] implementors
+//! this tree defines, and `Add`/
+//! `BinaryALUInstruction`/`BranchInstruction` appear only as `ToText`/
+//! `FromText` stubs in `generated_instructions.rs`, never as something a
+//! `downcast_ref` can find in an instruction list. Wiring them in is a
+//! prerequisite that belongs to the instruction-set definition, not to
+//! constant folding, so it's out of scope for this file to invent.
+//!
+//! what's actually implemented below, and all that's implemented, is the
+//! evaluation half such a pass would eventually call into: `fold_binary`/
+//! `fold_unary` operating directly on interned [[`crate::consts::Const`]]
+//! values, extended to every `Const` variant they can give a sound answer
+//! for. Treat this request as still open, not closed out by the
+//! aggregate-folding work below.
+
+// TODO: implement the worklist-based constant-propagation pass described
+// above; `fold_binary`/`fold_unary` alone don't satisfy that request, so
+// don't treat it as done until this pass exists.
+
+use crate::consts::{
+    Const, ConstArray, ConstFloat, ConstInteger, ConstScalableVector, ConstStruct, ConstVector,
+    Float16, Float32, Float64, RelaxedFloat32, RelaxedInt32,
+};
+use crate::prelude::*;
+use crate::{BoolType, FloatType, IntegerType};
+use std::fmt;
+
+/// the binary operations [`fold_binary`] can evaluate
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum BinaryOp {
+    /// wrapping addition
+    Add,
+    /// wrapping subtraction
+    Sub,
+    /// wrapping multiplication
+    Mul,
+    /// bitwise and
+    And,
+    /// bitwise or
+    Or,
+    /// bitwise xor
+    Xor,
+    /// left shift; the shift amount is masked to the operand's bit width
+    Shl,
+    /// logical (zero-filling) right shift; the shift amount is masked to the operand's bit width
+    LShr,
+    /// arithmetic (sign-filling) right shift; the shift amount is masked to the operand's bit width
+    AShr,
+    /// equal to
+    CmpEq,
+    /// not equal to
+    CmpNe,
+    /// less than, treating the operands as signed
+    CmpLtSigned,
+    /// less than, treating the operands as unsigned
+    CmpLtUnsigned,
+    /// less than or equal to, treating the operands as signed
+    CmpLeSigned,
+    /// less than or equal to, treating the operands as unsigned
+    CmpLeUnsigned,
+    /// greater than, treating the operands as signed
+    CmpGtSigned,
+    /// greater than, treating the operands as unsigned
+    CmpGtUnsigned,
+    /// greater than or equal to, treating the operands as signed
+    CmpGeSigned,
+    /// greater than or equal to, treating the operands as unsigned
+    CmpGeUnsigned,
+}
+
+/// the unary operations [`fold_unary`] can evaluate
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum UnaryOp {
+    /// wrapping negation (flips the sign bit for floats)
+    Neg,
+    /// bitwise complement (logical negation for `Bool`)
+    Not,
+}
+
+/// an error produced while folding a constant expression
+#[derive(Clone, Debug)]
+pub enum ConstEvalError<'g> {
+    /// `fold_binary`'s two operands don't have the same type
+    TypeMismatch {
+        /// the left operand's type
+        lhs_type: Interned<'g, Type<'g>>,
+        /// the right operand's type
+        rhs_type: Interned<'g, Type<'g>>,
+    },
+    /// the operation has no defined meaning for the operand type
+    UnsupportedOperation {
+        /// the name of the operation that was attempted
+        op: &'static str,
+        /// the operand type the operation was attempted on
+        const_type: Interned<'g, Type<'g>>,
+    },
+}
+
+impl fmt::Display for ConstEvalError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstEvalError::TypeMismatch { lhs_type, rhs_type } => write!(
+                f,
+                "const-eval type mismatch: {} vs {}",
+                lhs_type.display(),
+                rhs_type.display()
+            ),
+            ConstEvalError::UnsupportedOperation { op, const_type } => write!(
+                f,
+                "const-eval: {} is not defined for {}",
+                op,
+                const_type.display()
+            ),
+        }
+    }
+}
+
+/// truncate `u` to the low `n` bits
+fn clip(u: u128, n: u32) -> u128 {
+    u & (u128::MAX >> (128 - n))
+}
+
+/// sign-extend the low `n` bits of `u` to fill all 128 bits
+fn sext(u: u128, n: u32) -> u128 {
+    (((u << (128 - n)) as i128) >> (128 - n)) as u128
+}
+
+/// the bit width of an `IntegerType`
+fn bit_width(ty: IntegerType) -> u32 {
+    match ty {
+        IntegerType::Int8 => 8,
+        IntegerType::Int16 => 16,
+        IntegerType::Int32 => 32,
+        IntegerType::RelaxedInt32 => 32,
+        IntegerType::Int64 => 64,
+    }
+}
+
+fn integer_to_u128(v: ConstInteger) -> u128 {
+    match v {
+        ConstInteger::Int8(v) => v as u128,
+        ConstInteger::Int16(v) => v as u128,
+        ConstInteger::Int32(v) => v as u128,
+        ConstInteger::RelaxedInt32(RelaxedInt32(v)) => v as u128,
+        ConstInteger::Int64(v) => v as u128,
+    }
+}
+
+fn integer_from_u128(ty: IntegerType, v: u128) -> ConstInteger {
+    let v = clip(v, bit_width(ty));
+    match ty {
+        IntegerType::Int8 => ConstInteger::Int8(v as u8),
+        IntegerType::Int16 => ConstInteger::Int16(v as u16),
+        IntegerType::Int32 => ConstInteger::Int32(v as u32),
+        IntegerType::RelaxedInt32 => ConstInteger::RelaxedInt32(RelaxedInt32(v as u32)),
+        IntegerType::Int64 => ConstInteger::Int64(v as u64),
+    }
+}
+
+fn fold_binary_integer<'g>(op: BinaryOp, lhs: ConstInteger, rhs: ConstInteger) -> Const<'g> {
+    let ty = lhs.get_type();
+    let n = bit_width(ty);
+    let lhs_bits = integer_to_u128(lhs);
+    let rhs_bits = integer_to_u128(rhs);
+    // wrapping add/sub/mul have the same bit pattern whether the operands are
+    // interpreted as signed or unsigned, so there's no need to `sext` first
+    let unsigned_result = |f: fn(u128, u128) -> u128| integer_from_u128(ty, f(lhs_bits, rhs_bits));
+    let shift_amount = (rhs_bits as u32) & (n - 1);
+    match op {
+        BinaryOp::Add => Const::Integer(unsigned_result(u128::wrapping_add)),
+        BinaryOp::Sub => Const::Integer(unsigned_result(u128::wrapping_sub)),
+        BinaryOp::Mul => Const::Integer(unsigned_result(u128::wrapping_mul)),
+        BinaryOp::And => Const::Integer(integer_from_u128(ty, lhs_bits & rhs_bits)),
+        BinaryOp::Or => Const::Integer(integer_from_u128(ty, lhs_bits | rhs_bits)),
+        BinaryOp::Xor => Const::Integer(integer_from_u128(ty, lhs_bits ^ rhs_bits)),
+        BinaryOp::Shl => Const::Integer(integer_from_u128(ty, lhs_bits << shift_amount)),
+        BinaryOp::LShr => Const::Integer(integer_from_u128(ty, lhs_bits >> shift_amount)),
+        BinaryOp::AShr => Const::Integer(integer_from_u128(
+            ty,
+            ((sext(lhs_bits, n) as i128) >> shift_amount) as u128,
+        )),
+        BinaryOp::CmpEq => Const::Bool(lhs_bits == rhs_bits),
+        BinaryOp::CmpNe => Const::Bool(lhs_bits != rhs_bits),
+        BinaryOp::CmpLtUnsigned => Const::Bool(lhs_bits < rhs_bits),
+        BinaryOp::CmpLeUnsigned => Const::Bool(lhs_bits <= rhs_bits),
+        BinaryOp::CmpGtUnsigned => Const::Bool(lhs_bits > rhs_bits),
+        BinaryOp::CmpGeUnsigned => Const::Bool(lhs_bits >= rhs_bits),
+        BinaryOp::CmpLtSigned => {
+            Const::Bool((sext(lhs_bits, n) as i128) < (sext(rhs_bits, n) as i128))
+        }
+        BinaryOp::CmpLeSigned => {
+            Const::Bool((sext(lhs_bits, n) as i128) <= (sext(rhs_bits, n) as i128))
+        }
+        BinaryOp::CmpGtSigned => {
+            Const::Bool((sext(lhs_bits, n) as i128) > (sext(rhs_bits, n) as i128))
+        }
+        BinaryOp::CmpGeSigned => {
+            Const::Bool((sext(lhs_bits, n) as i128) >= (sext(rhs_bits, n) as i128))
+        }
+    }
+}
+
+fn fold_binary_bool<'g>(
+    op: BinaryOp,
+    lhs: bool,
+    rhs: bool,
+    global_state: &'g GlobalState<'g>,
+) -> Result<Const<'g>, ConstEvalError<'g>> {
+    match op {
+        BinaryOp::And => Ok(Const::Bool(lhs & rhs)),
+        BinaryOp::Or => Ok(Const::Bool(lhs | rhs)),
+        BinaryOp::Xor => Ok(Const::Bool(lhs ^ rhs)),
+        BinaryOp::CmpEq => Ok(Const::Bool(lhs == rhs)),
+        BinaryOp::CmpNe => Ok(Const::Bool(lhs != rhs)),
+        _ => Err(ConstEvalError::UnsupportedOperation {
+            op: op.name(),
+            const_type: BoolType.intern(global_state),
+        }),
+    }
+}
+
+/// decode a `ConstFloat` into a native `f64`, or `None` for `Float16` (this crate has no
+/// half-precision arithmetic to fall back on)
+fn float_to_f64(v: ConstFloat) -> Option<f64> {
+    match v {
+        ConstFloat::Float16(_) => None,
+        ConstFloat::Float32(Float32(bits)) => Some(f32::from_bits(bits) as f64),
+        ConstFloat::RelaxedFloat32(RelaxedFloat32(bits)) => Some(f32::from_bits(bits) as f64),
+        ConstFloat::Float64(Float64(bits)) => Some(f64::from_bits(bits)),
+    }
+}
+
+fn float_from_f64(ty: FloatType, v: f64) -> ConstFloat {
+    match ty {
+        FloatType::Float16 => unreachable!("Float16 arithmetic is never attempted"),
+        FloatType::Float32 => ConstFloat::Float32(Float32((v as f32).to_bits())),
+        FloatType::RelaxedFloat32 => {
+            ConstFloat::RelaxedFloat32(RelaxedFloat32((v as f32).to_bits()))
+        }
+        FloatType::Float64 => ConstFloat::Float64(Float64(v.to_bits())),
+    }
+}
+
+fn fold_binary_float<'g>(
+    op: BinaryOp,
+    lhs: ConstFloat,
+    rhs: ConstFloat,
+    global_state: &'g GlobalState<'g>,
+) -> Result<Const<'g>, ConstEvalError<'g>> {
+    let ty = lhs.get_type();
+    if op == BinaryOp::CmpEq || op == BinaryOp::CmpNe {
+        // bit-exact comparison is always defined, even for `Float16`
+        let lhs_bits = float_bits(lhs);
+        let rhs_bits = float_bits(rhs);
+        return Ok(Const::Bool(if op == BinaryOp::CmpEq {
+            lhs_bits == rhs_bits
+        } else {
+            lhs_bits != rhs_bits
+        }));
+    }
+    let (lhs, rhs) = match (float_to_f64(lhs), float_to_f64(rhs)) {
+        (Some(lhs), Some(rhs)) => (lhs, rhs),
+        _ => {
+            return Err(ConstEvalError::UnsupportedOperation {
+                op: op.name(),
+                const_type: ty.intern(global_state),
+            })
+        }
+    };
+    match op {
+        BinaryOp::Add => Ok(Const::Float(float_from_f64(ty, lhs + rhs))),
+        BinaryOp::Sub => Ok(Const::Float(float_from_f64(ty, lhs - rhs))),
+        BinaryOp::Mul => Ok(Const::Float(float_from_f64(ty, lhs * rhs))),
+        _ => Err(ConstEvalError::UnsupportedOperation {
+            op: op.name(),
+            const_type: ty.intern(global_state),
+        }),
+    }
+}
+
+fn float_bits(v: ConstFloat) -> u64 {
+    match v {
+        ConstFloat::Float16(Float16(bits)) => bits as u64,
+        ConstFloat::Float32(Float32(bits)) => bits as u64,
+        ConstFloat::RelaxedFloat32(RelaxedFloat32(bits)) => bits as u64,
+        ConstFloat::Float64(Float64(bits)) => bits,
+    }
+}
+
+fn fold_binary_vector<'g>(
+    op: BinaryOp,
+    lhs: &ConstVector<'g>,
+    rhs: &ConstVector<'g>,
+    global_state: &'g GlobalState<'g>,
+) -> Result<Const<'g>, ConstEvalError<'g>> {
+    if lhs.element_type() != rhs.element_type() || lhs.elements().len() != rhs.elements().len() {
+        return Err(ConstEvalError::TypeMismatch {
+            lhs_type: lhs.get_type(global_state),
+            rhs_type: rhs.get_type(global_state),
+        });
+    }
+    let elements = lhs
+        .elements()
+        .iter()
+        .zip(rhs.elements())
+        .map(|(lhs, rhs)| fold_binary(op, lhs.get(), rhs.get(), global_state))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Const::Vector(ConstVector::new(elements, global_state)))
+}
+
+/// a `ConstScalableVector` is `element` repeated across an unknown-at-compile-time number of
+/// lanes, so `op` applied lane-wise is just `op` applied once to the two `element`s -- every
+/// lane would compute the same result, whatever `vscale` turns out to be at run time.
+fn fold_binary_scalable_vector<'g>(
+    op: BinaryOp,
+    lhs: &ConstScalableVector<'g>,
+    rhs: &ConstScalableVector<'g>,
+    global_state: &'g GlobalState<'g>,
+) -> Result<Const<'g>, ConstEvalError<'g>> {
+    if lhs.element_type() != rhs.element_type() || lhs.len() != rhs.len() {
+        return Err(ConstEvalError::TypeMismatch {
+            lhs_type: lhs.get_type(global_state),
+            rhs_type: rhs.get_type(global_state),
+        });
+    }
+    let element = fold_binary(op, lhs.element().get(), rhs.element().get(), global_state)?;
+    Ok(Const::ScalableVector(ConstScalableVector::new(
+        element,
+        lhs.len(),
+        global_state,
+    )))
+}
+
+fn fold_binary_array<'g>(
+    op: BinaryOp,
+    lhs: &ConstArray<'g>,
+    rhs: &ConstArray<'g>,
+    global_state: &'g GlobalState<'g>,
+) -> Result<Const<'g>, ConstEvalError<'g>> {
+    if lhs.element_type() != rhs.element_type() || lhs.elements().len() != rhs.elements().len() {
+        return Err(ConstEvalError::TypeMismatch {
+            lhs_type: lhs.get_type(global_state),
+            rhs_type: rhs.get_type(global_state),
+        });
+    }
+    let elements = lhs
+        .elements()
+        .iter()
+        .zip(rhs.elements())
+        .map(|(lhs, rhs)| fold_binary(op, lhs.get(), rhs.get(), global_state))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Const::Array(ConstArray::new(elements, global_state)))
+}
+
+fn fold_binary_struct<'g>(
+    op: BinaryOp,
+    lhs: &ConstStruct<'g>,
+    rhs: &ConstStruct<'g>,
+    global_state: &'g GlobalState<'g>,
+) -> Result<Const<'g>, ConstEvalError<'g>> {
+    if lhs.struct_type() != rhs.struct_type() {
+        return Err(ConstEvalError::TypeMismatch {
+            lhs_type: lhs.get_type(global_state),
+            rhs_type: rhs.get_type(global_state),
+        });
+    }
+    let fields = lhs
+        .fields()
+        .iter()
+        .zip(rhs.fields())
+        .map(|(lhs, rhs)| fold_binary(op, lhs.get(), rhs.get(), global_state))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Const::Struct(ConstStruct::new(fields, global_state)))
+}
+
+/// fold the binary operation `op` applied to `lhs` and `rhs`, returning the newly interned
+/// result or a [`ConstEvalError`] if `op` isn't defined for the operands' type.
+///
+/// # Errors
+///
+/// returns [`ConstEvalError::TypeMismatch`] if `lhs.get_type() != rhs.get_type()`, or
+/// [`ConstEvalError::UnsupportedOperation`] if `op` has no defined meaning for that type (for
+/// example, `Shl` on a `Bool`, or any arithmetic op on a `Float16`).
+pub fn fold_binary<'g>(
+    op: BinaryOp,
+    lhs: &Const<'g>,
+    rhs: &Const<'g>,
+    global_state: &'g GlobalState<'g>,
+) -> Result<Interned<'g, Const<'g>>, ConstEvalError<'g>> {
+    let lhs_type = lhs.get_type(global_state);
+    let rhs_type = rhs.get_type(global_state);
+    if lhs_type != rhs_type {
+        return Err(ConstEvalError::TypeMismatch { lhs_type, rhs_type });
+    }
+    let retval = match (lhs, rhs) {
+        (Const::Integer(lhs), Const::Integer(rhs)) => fold_binary_integer(op, *lhs, *rhs),
+        (Const::Bool(lhs), Const::Bool(rhs)) => fold_binary_bool(op, *lhs, *rhs, global_state)?,
+        (Const::Float(lhs), Const::Float(rhs)) => fold_binary_float(op, *lhs, *rhs, global_state)?,
+        (Const::Vector(lhs), Const::Vector(rhs)) => {
+            return Ok(fold_binary_vector(op, lhs, rhs, global_state)?.intern(global_state))
+        }
+        (Const::ScalableVector(lhs), Const::ScalableVector(rhs)) => {
+            return Ok(fold_binary_scalable_vector(op, lhs, rhs, global_state)?.intern(global_state))
+        }
+        (Const::Array(lhs), Const::Array(rhs)) => {
+            return Ok(fold_binary_array(op, lhs, rhs, global_state)?.intern(global_state))
+        }
+        (Const::Struct(lhs), Const::Struct(rhs)) => {
+            return Ok(fold_binary_struct(op, lhs, rhs, global_state)?.intern(global_state))
+        }
+        _ => {
+            return Err(ConstEvalError::UnsupportedOperation {
+                op: op.name(),
+                const_type: lhs_type,
+            })
+        }
+    };
+    Ok(retval.intern(global_state))
+}
+
+fn fold_unary_integer(op: UnaryOp, operand: ConstInteger) -> ConstInteger {
+    let ty = operand.get_type();
+    let n = bit_width(ty);
+    let bits = integer_to_u128(operand);
+    match op {
+        UnaryOp::Neg => integer_from_u128(ty, clip(bits.wrapping_neg(), n)),
+        UnaryOp::Not => integer_from_u128(ty, !bits),
+    }
+}
+
+/// fold the unary operation `op` applied to `operand`, returning the newly interned result or
+/// a [`ConstEvalError`] if `op` isn't defined for `operand`'s type (for example, `Neg` on a
+/// `Bool`, or `Not` on a `Float`).
+pub fn fold_unary<'g>(
+    op: UnaryOp,
+    operand: &Const<'g>,
+    global_state: &'g GlobalState<'g>,
+) -> Result<Interned<'g, Const<'g>>, ConstEvalError<'g>> {
+    let retval = match operand {
+        Const::Integer(v) => Const::Integer(fold_unary_integer(op, *v)),
+        Const::Bool(v) => match op {
+            UnaryOp::Not => Const::Bool(!v),
+            UnaryOp::Neg => {
+                return Err(ConstEvalError::UnsupportedOperation {
+                    op: op.name(),
+                    const_type: BoolType.intern(global_state),
+                })
+            }
+        },
+        Const::Float(v) => match op {
+            // flipping the sign bit is always defined, even for `Float16`
+            UnaryOp::Neg => Const::Float(float_with_bits(*v, float_bits(*v) ^ sign_bit_mask(*v))),
+            UnaryOp::Not => {
+                return Err(ConstEvalError::UnsupportedOperation {
+                    op: op.name(),
+                    const_type: v.get_type().intern(global_state),
+                })
+            }
+        },
+        Const::Vector(v) => {
+            let elements = v
+                .elements()
+                .iter()
+                .map(|element| fold_unary(op, element.get(), global_state))
+                .collect::<Result<Vec<_>, _>>()?;
+            Const::Vector(ConstVector::new(elements, global_state))
+        }
+        // see `fold_binary_scalable_vector`: every lane holds the same value, so folding
+        // the repeated element once stands in for folding every (unknown-count) lane
+        Const::ScalableVector(v) => {
+            let element = fold_unary(op, v.element().get(), global_state)?;
+            Const::ScalableVector(ConstScalableVector::new(element, v.len(), global_state))
+        }
+        Const::Array(v) => {
+            let elements = v
+                .elements()
+                .iter()
+                .map(|element| fold_unary(op, element.get(), global_state))
+                .collect::<Result<Vec<_>, _>>()?;
+            Const::Array(ConstArray::new(elements, global_state))
+        }
+        Const::Struct(v) => {
+            let fields = v
+                .fields()
+                .iter()
+                .map(|field| fold_unary(op, field.get(), global_state))
+                .collect::<Result<Vec<_>, _>>()?;
+            Const::Struct(ConstStruct::new(fields, global_state))
+        }
+        _ => {
+            return Err(ConstEvalError::UnsupportedOperation {
+                op: op.name(),
+                const_type: operand.get_type(global_state),
+            })
+        }
+    };
+    Ok(retval.intern(global_state))
+}
+
+fn sign_bit_mask(v: ConstFloat) -> u64 {
+    match v {
+        ConstFloat::Float16(_) => 0x8000,
+        ConstFloat::Float32(_) | ConstFloat::RelaxedFloat32(_) => 0x8000_0000,
+        ConstFloat::Float64(_) => 0x8000_0000_0000_0000,
+    }
+}
+
+fn float_with_bits(ty: ConstFloat, bits: u64) -> ConstFloat {
+    match ty {
+        ConstFloat::Float16(_) => ConstFloat::Float16(Float16(bits as u16)),
+        ConstFloat::Float32(_) => ConstFloat::Float32(Float32(bits as u32)),
+        ConstFloat::RelaxedFloat32(_) => ConstFloat::RelaxedFloat32(RelaxedFloat32(bits as u32)),
+        ConstFloat::Float64(_) => ConstFloat::Float64(Float64(bits)),
+    }
+}
+
+impl BinaryOp {
+    fn name(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "Add",
+            BinaryOp::Sub => "Sub",
+            BinaryOp::Mul => "Mul",
+            BinaryOp::And => "And",
+            BinaryOp::Or => "Or",
+            BinaryOp::Xor => "Xor",
+            BinaryOp::Shl => "Shl",
+            BinaryOp::LShr => "LShr",
+            BinaryOp::AShr => "AShr",
+            BinaryOp::CmpEq => "CmpEq",
+            BinaryOp::CmpNe => "CmpNe",
+            BinaryOp::CmpLtSigned => "CmpLtSigned",
+            BinaryOp::CmpLtUnsigned => "CmpLtUnsigned",
+            BinaryOp::CmpLeSigned => "CmpLeSigned",
+            BinaryOp::CmpLeUnsigned => "CmpLeUnsigned",
+            BinaryOp::CmpGtSigned => "CmpGtSigned",
+            BinaryOp::CmpGtUnsigned => "CmpGtUnsigned",
+            BinaryOp::CmpGeSigned => "CmpGeSigned",
+            BinaryOp::CmpGeUnsigned => "CmpGeUnsigned",
+        }
+    }
+}
+
+impl UnaryOp {
+    fn name(self) -> &'static str {
+        match self {
+            UnaryOp::Neg => "Neg",
+            UnaryOp::Not => "Not",
+        }
+    }
+}
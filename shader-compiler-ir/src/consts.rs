@@ -4,15 +4,16 @@
 use crate::{
     prelude::*,
     text::{
-        FromTextError, FromTextState, FromToTextListForm, IntegerSuffix, IntegerToken, Keyword,
+        f16_bits_to_f64, f64_to_f16_bits_round_to_nearest_even, FloatToken, FromTextError,
+        FromTextState, FromToTextListForm, IntegerSuffix, IntegerToken, Keyword, ListForm,
         Punctuation, ToTextState, TokenKind,
     },
-    BoolType, FloatType, IntegerType, PointerType, VectorType,
+    ArrayType, BoolType, FloatType, IntegerType, PointerType, StructType, VectorType,
 };
 use alloc::vec::Vec;
 use core::{
     convert::{TryFrom, TryInto},
-    fmt,
+    fmt, iter,
 };
 
 /// a constant integer
@@ -127,6 +128,23 @@ impl ConstInteger {
             ConstInteger::Int64(_) => IntegerType::Int64,
         }
     }
+
+    /// reinterpret `self`'s bits as a `ConstFloat` of the same bit width
+    ///
+    /// # Errors
+    ///
+    /// returns `Err(InvalidFloatSize)` for `Int8`, since there is no 8-bit float type to reinterpret it as
+    pub fn bitcast_to_float(self) -> Result<ConstFloat, InvalidFloatSize> {
+        match self {
+            ConstInteger::Int8(_) => Err(InvalidFloatSize),
+            ConstInteger::Int16(v) => Ok(ConstFloat::Float16(Float16(v))),
+            ConstInteger::Int32(v) => Ok(ConstFloat::Float32(Float32(v))),
+            ConstInteger::RelaxedInt32(RelaxedInt32(v)) => {
+                Ok(ConstFloat::RelaxedFloat32(RelaxedFloat32(v)))
+            }
+            ConstInteger::Int64(v) => Ok(ConstFloat::Float64(Float64(v))),
+        }
+    }
 }
 
 /// a constant 16-bit float. The bits are stored as a `u16` in `Float16.0`.
@@ -187,6 +205,18 @@ impl ConstFloat {
             ConstFloat::Float64(_) => FloatType::Float64,
         }
     }
+
+    /// reinterpret `self`'s bits as a `ConstInteger` of the same bit width
+    pub fn bitcast_to_integer(self) -> ConstInteger {
+        match self {
+            ConstFloat::Float16(Float16(v)) => ConstInteger::Int16(v),
+            ConstFloat::Float32(Float32(v)) => ConstInteger::Int32(v),
+            ConstFloat::RelaxedFloat32(RelaxedFloat32(v)) => {
+                ConstInteger::RelaxedInt32(RelaxedInt32(v))
+            }
+            ConstFloat::Float64(Float64(v)) => ConstInteger::Int64(v),
+        }
+    }
 }
 
 impl From<ConstFloat> for Const<'_> {
@@ -257,6 +287,69 @@ impl<'g> ConstVector<'g> {
         }
         .intern(global_state)
     }
+    /// create a new `ConstVector` by repeating `element` `len` times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len == 0`.
+    pub fn splat(
+        element: impl Internable<'g, Interned = Const<'g>>,
+        len: usize,
+        global_state: &'g GlobalState<'g>,
+    ) -> Self {
+        assert_ne!(len, 0, "vector must have non-zero size");
+        let element = element.intern(global_state);
+        ConstVector::new(iter::repeat(element).take(len), global_state)
+    }
+    /// get the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> Interned<'g, Const<'g>> {
+        self.elements[index]
+    }
+    /// create a new `ConstVector` by applying `f` to each element in turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the results of `f` don't all have the same type.
+    pub fn map<R: Internable<'g, Interned = Const<'g>>>(
+        &self,
+        global_state: &'g GlobalState<'g>,
+        mut f: impl FnMut(Interned<'g, Const<'g>>) -> R,
+    ) -> Self {
+        ConstVector::new(
+            self.elements.iter().map(|&element| f(element)),
+            global_state,
+        )
+    }
+    /// create a new `ConstVector` by applying `f` to each pair of corresponding elements of
+    /// `self` and `other` in turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` don't have the same number of elements.
+    /// Panics if the results of `f` don't all have the same type.
+    pub fn zip_map<R: Internable<'g, Interned = Const<'g>>>(
+        &self,
+        other: &Self,
+        global_state: &'g GlobalState<'g>,
+        mut f: impl FnMut(Interned<'g, Const<'g>>, Interned<'g, Const<'g>>) -> R,
+    ) -> Self {
+        assert_eq!(
+            self.elements.len(),
+            other.elements.len(),
+            "vectors must have the same number of elements"
+        );
+        ConstVector::new(
+            self.elements
+                .iter()
+                .zip(other.elements.iter())
+                .map(|(&a, &b)| f(a, b)),
+            global_state,
+        )
+    }
 }
 
 impl<'g> Internable<'g> for ConstVector<'g> {
@@ -272,6 +365,194 @@ impl<'g> From<ConstVector<'g>> for Const<'g> {
     }
 }
 
+/// a constant scalable vector: `element` repeated across `len` lanes, times however many
+/// multiples of `len` the hardware's `vscale` turns out to be at run time.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct ConstScalableVector<'g> {
+    element_type: Interned<'g, Type<'g>>,
+    element: Interned<'g, Const<'g>>,
+    len: usize,
+}
+
+impl<'g> ConstScalableVector<'g> {
+    /// create a new `ConstScalableVector` splatting `element` across `len` lanes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    pub fn new(
+        element: impl Internable<'g, Interned = Const<'g>>,
+        len: usize,
+        global_state: &'g GlobalState<'g>,
+    ) -> Self {
+        assert_ne!(len, 0, "scalable vector must have non-zero minimum length");
+        let element = element.intern(global_state);
+        let element_type = element.get().get_type(global_state);
+        ConstScalableVector {
+            element_type,
+            element,
+            len,
+        }
+    }
+    /// get the type of an element.
+    pub fn element_type(&self) -> Interned<'g, Type<'g>> {
+        self.element_type
+    }
+    /// get the repeated element.
+    pub fn element(&self) -> Interned<'g, Const<'g>> {
+        self.element
+    }
+    /// get the minimum vector length, i.e. the length when `vscale == 1`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// get `self`'s type
+    pub fn get_type(&self, global_state: &'g GlobalState<'g>) -> Interned<'g, Type> {
+        VectorType {
+            element: self.element_type,
+            scalable: true,
+            len: self.len,
+        }
+        .intern(global_state)
+    }
+}
+
+impl<'g> Internable<'g> for ConstScalableVector<'g> {
+    type Interned = Const<'g>;
+    fn intern(&self, global_state: &'g GlobalState<'g>) -> Interned<'g, Const<'g>> {
+        Const::from(self.clone()).intern(global_state)
+    }
+}
+
+impl<'g> From<ConstScalableVector<'g>> for Const<'g> {
+    fn from(v: ConstScalableVector<'g>) -> Self {
+        Const::ScalableVector(v)
+    }
+}
+
+/// a constant non-empty array.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct ConstArray<'g> {
+    element_type: Interned<'g, Type<'g>>,
+    elements: Vec<Interned<'g, Const<'g>>>,
+}
+
+impl<'g> ConstArray<'g> {
+    /// create a new `ConstArray` using the provided elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no provided elements.
+    /// Panics if not all elements are the same type.
+    pub fn new(
+        elements: impl IntoIterator<Item = impl Internable<'g, Interned = Const<'g>>>,
+        global_state: &'g GlobalState<'g>,
+    ) -> Self {
+        let elements: Vec<_> = elements
+            .into_iter()
+            .map(|v| v.intern(global_state))
+            .collect();
+        let mut iter = elements.iter();
+        let element_type = iter
+            .next()
+            .expect("array must have non-zero size")
+            .get()
+            .get_type(global_state);
+        for element in iter {
+            assert_eq!(
+                element.get().get_type(global_state),
+                element_type,
+                "array must have consistent type"
+            );
+        }
+        ConstArray {
+            element_type,
+            elements,
+        }
+    }
+    /// get the type of an element.
+    pub fn element_type(&self) -> Interned<'g, Type<'g>> {
+        self.element_type
+    }
+    /// get the elements.
+    pub fn elements(&self) -> &[Interned<'g, Const<'g>>] {
+        &self.elements
+    }
+    /// get `self`'s type
+    pub fn get_type(&self, global_state: &'g GlobalState<'g>) -> Interned<'g, Type> {
+        ArrayType {
+            element: self.element_type,
+            len: self.elements.len(),
+        }
+        .intern(global_state)
+    }
+}
+
+impl<'g> Internable<'g> for ConstArray<'g> {
+    type Interned = Const<'g>;
+    fn intern(&self, global_state: &'g GlobalState<'g>) -> Interned<'g, Const<'g>> {
+        Const::from(self.clone()).intern(global_state)
+    }
+}
+
+impl<'g> From<ConstArray<'g>> for Const<'g> {
+    fn from(v: ConstArray<'g>) -> Self {
+        Const::Array(v)
+    }
+}
+
+/// a constant struct: ordered, possibly heterogeneous fields.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct ConstStruct<'g> {
+    struct_type: StructType<'g>,
+    fields: Vec<Interned<'g, Const<'g>>>,
+}
+
+impl<'g> ConstStruct<'g> {
+    /// create a new `ConstStruct` using the provided fields, in order.
+    pub fn new(
+        fields: impl IntoIterator<Item = impl Internable<'g, Interned = Const<'g>>>,
+        global_state: &'g GlobalState<'g>,
+    ) -> Self {
+        let fields: Vec<_> = fields.into_iter().map(|v| v.intern(global_state)).collect();
+        let struct_type = StructType {
+            members: fields
+                .iter()
+                .map(|field| field.get().get_type(global_state))
+                .collect(),
+        };
+        ConstStruct {
+            struct_type,
+            fields,
+        }
+    }
+    /// get the type of the struct's fields.
+    pub fn struct_type(&self) -> &StructType<'g> {
+        &self.struct_type
+    }
+    /// get the fields.
+    pub fn fields(&self) -> &[Interned<'g, Const<'g>>] {
+        &self.fields
+    }
+    /// get `self`'s type
+    pub fn get_type(&self, global_state: &'g GlobalState<'g>) -> Interned<'g, Type> {
+        self.struct_type.clone().intern(global_state)
+    }
+}
+
+impl<'g> Internable<'g> for ConstStruct<'g> {
+    type Interned = Const<'g>;
+    fn intern(&self, global_state: &'g GlobalState<'g>) -> Interned<'g, Const<'g>> {
+        Const::from(self.clone()).intern(global_state)
+    }
+}
+
+impl<'g> From<ConstStruct<'g>> for Const<'g> {
+    fn from(v: ConstStruct<'g>) -> Self {
+        Const::Struct(v)
+    }
+}
+
 /// a constant.
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub enum Const<'g> {
@@ -283,7 +564,12 @@ pub enum Const<'g> {
     Bool(bool),
     /// a constant vector
     Vector(ConstVector<'g>),
-    // FIXME: add scalable vectors
+    /// a constant scalable vector
+    ScalableVector(ConstScalableVector<'g>),
+    /// a constant array
+    Array(ConstArray<'g>),
+    /// a constant struct
+    Struct(ConstStruct<'g>),
     /// a `undef` constant
     Undef(Interned<'g, Type<'g>>),
     /// a null pointer constant
@@ -326,6 +612,9 @@ impl<'g> Const<'g> {
             Const::Float(const_float) => const_float.get_type().intern(global_state),
             Const::Bool(_) => BoolType.intern(global_state),
             Const::Vector(ref const_vector) => const_vector.get_type(global_state),
+            Const::ScalableVector(ref const_vector) => const_vector.get_type(global_state),
+            Const::Array(ref const_array) => const_array.get_type(global_state),
+            Const::Struct(ref const_struct) => const_struct.get_type(global_state),
             Const::Undef(retval) => retval,
             Const::Null(ref pointer_type) => pointer_type.intern(global_state),
             Const::Function(ref function) => function.function_type.intern(global_state),
@@ -381,17 +670,62 @@ impl<'g> ToText<'g> for ConstInteger {
     }
 }
 
+/// build the `ConstFloat` of type `float_type` nearest to `value`, rounding to nearest, ties to
+/// even (exact for `Float32`/`RelaxedFloat32`/`Float64`, since `value` already came from parsing
+/// decimal text as an `f64`)
+fn const_float_from_f64(float_type: FloatType, value: f64) -> ConstFloat {
+    match float_type {
+        FloatType::Float16 => {
+            ConstFloat::Float16(Float16(f64_to_f16_bits_round_to_nearest_even(value)))
+        }
+        FloatType::Float32 => ConstFloat::Float32(Float32((value as f32).to_bits())),
+        FloatType::RelaxedFloat32 => {
+            ConstFloat::RelaxedFloat32(RelaxedFloat32((value as f32).to_bits()))
+        }
+        FloatType::Float64 => ConstFloat::Float64(Float64(value.to_bits())),
+    }
+}
+
 impl FromToTextListForm for ConstFloat {}
 
 impl<'g> FromText<'g> for ConstFloat {
     type Parsed = Self;
     fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
         let float_type = FloatType::from_text(state)?;
+        let negative = state.peek_token()?.kind.punct() == Some(Punctuation::Minus);
+        if negative {
+            state.parse_token()?;
+        }
+        if let Some(identifier) = state.peek_token()?.kind.identifier() {
+            let value = match (identifier, negative) {
+                ("inf", false) => Some(f64::INFINITY),
+                ("inf", true) => Some(f64::NEG_INFINITY),
+                ("nan", false) => Some(f64::NAN),
+                _ => None,
+            };
+            if let Some(value) = value {
+                state.parse_token()?;
+                return Ok(const_float_from_f64(float_type, value));
+            }
+        }
+        if let Some(FloatToken { bits, suffix }) = state.peek_token()?.kind.float() {
+            if suffix != None {
+                state.error_at_peek_token("float literal must not have suffix")?;
+            }
+            let value = f64::from_bits(bits);
+            let value = if negative { -value } else { value };
+            state.parse_token()?;
+            return Ok(const_float_from_f64(float_type, value));
+        }
+        if negative {
+            return state
+                .error_at_peek_token("expected float literal after '-'")?
+                .into();
+        }
+        // a bare integer literal (no suffix) is the float's raw bit pattern, not its decimal value
         let IntegerToken { value, suffix } = match state.peek_token()?.kind.integer() {
             Some(v) => v,
-            _ => state
-                .error_at_peek_token("expected integer literal")?
-                .into(),
+            _ => state.error_at_peek_token("expected float literal")?.into(),
         };
         if suffix != None {
             state.error_at_peek_token("integer literal must not have suffix")?;
@@ -419,7 +753,14 @@ impl FromToTextListForm for Float16 {}
 
 impl<'g> ToText<'g> for Float16 {
     fn to_text(&self, state: &mut ToTextState<'g, '_>) -> fmt::Result {
-        write!(state, "f16 {:#X}", self.0)
+        let value = f16_bits_to_f64(self.0);
+        if value.is_finite() {
+            // `value` fits in an `f32` exactly (every half value does), so formatting through
+            // `f32` rather than `f64` gives the shortest decimal that still round-trips
+            write!(state, "f16 {}", value as f32)
+        } else {
+            write!(state, "f16 {:#X}", self.0)
+        }
     }
 }
 
@@ -427,7 +768,12 @@ impl FromToTextListForm for Float32 {}
 
 impl<'g> ToText<'g> for Float32 {
     fn to_text(&self, state: &mut ToTextState<'g, '_>) -> fmt::Result {
-        write!(state, "f32 {:#X}", self.0)
+        let value = f32::from_bits(self.0);
+        if value.is_finite() {
+            write!(state, "f32 {}", value)
+        } else {
+            write!(state, "f32 {:#X}", self.0)
+        }
     }
 }
 
@@ -435,7 +781,12 @@ impl FromToTextListForm for RelaxedFloat32 {}
 
 impl<'g> ToText<'g> for RelaxedFloat32 {
     fn to_text(&self, state: &mut ToTextState<'g, '_>) -> fmt::Result {
-        write!(state, "rf32 {:#X}", self.0)
+        let value = f32::from_bits(self.0);
+        if value.is_finite() {
+            write!(state, "rf32 {}", value)
+        } else {
+            write!(state, "rf32 {:#X}", self.0)
+        }
     }
 }
 
@@ -443,7 +794,12 @@ impl FromToTextListForm for Float64 {}
 
 impl<'g> ToText<'g> for Float64 {
     fn to_text(&self, state: &mut ToTextState<'g, '_>) -> fmt::Result {
-        write!(state, "f64 {:#X}", self.0)
+        let value = f64::from_bits(self.0);
+        if value.is_finite() {
+            write!(state, "f64 {}", value)
+        } else {
+            write!(state, "f64 {:#X}", self.0)
+        }
     }
 }
 
@@ -528,6 +884,117 @@ impl<'g> ToText<'g> for ConstVector<'g> {
     }
 }
 
+impl FromToTextListForm for ConstScalableVector<'_> {}
+
+impl<'g> FromText<'g> for ConstScalableVector<'g> {
+    type Parsed = Self;
+    fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
+        state.parse_parenthesized(
+            Punctuation::LessThan,
+            "missing scalable vector constant",
+            Punctuation::GreaterThan,
+            "missing closing angle bracket ('>')",
+            |state| -> Result<Self, FromTextError> {
+                let IntegerToken { value, suffix } = match state.peek_token()?.kind.integer() {
+                    Some(v) => v,
+                    _ => state
+                        .error_at_peek_token("missing scalable vector length")?
+                        .into(),
+                };
+                if suffix != None {
+                    state
+                        .error_at_peek_token("scalable vector length must not have type suffix")?;
+                }
+                let len = match usize::try_from(value) {
+                    Ok(len) if len != 0 => len,
+                    _ => state
+                        .error_at_peek_token("invalid scalable vector length")?
+                        .into(),
+                };
+                state.parse_token()?;
+                state
+                    .parse_keyword_token_or_error(Keyword::X, "missing 'x' after vector length")?;
+                state.parse_keyword_token_or_error(Keyword::Splat, "missing 'splat' after 'x'")?;
+                let element = Const::from_text(state)?;
+                Ok(ConstScalableVector::new(element, len, state.global_state()))
+            },
+        )
+    }
+}
+
+impl_display_as_to_text!(<'g> ConstScalableVector<'g>);
+
+impl<'g> ToText<'g> for ConstScalableVector<'g> {
+    fn to_text(&self, state: &mut ToTextState<'g, '_>) -> fmt::Result {
+        write!(state, "<{} x splat ", self.len)?;
+        self.element.to_text(state)?;
+        write!(state, ">")
+    }
+}
+
+impl FromToTextListForm for ConstArray<'_> {}
+
+impl<'g> FromText<'g> for ConstArray<'g> {
+    type Parsed = Self;
+    fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
+        state.parse_parenthesized(
+            Punctuation::LSquareBracket,
+            "missing array constant",
+            Punctuation::RSquareBracket,
+            "missing closing square bracket (']')",
+            |state| -> Result<Self, FromTextError> {
+                let element = Const::from_text(state)?;
+                let element_type = element.get().get_type(state.global_state());
+                let mut elements = vec![element];
+                while state.peek_token()?.kind.punct() == Some(Punctuation::Comma) {
+                    state.parse_token()?;
+                    let element_location = state.peek_token()?.span;
+                    let element = Const::from_text(state)?;
+                    if element.get().get_type(state.global_state()) != element_type {
+                        state.error_at(element_location, "array must have consistent type")?;
+                    }
+                    elements.push(element);
+                }
+                Ok(ConstArray::new(elements, state.global_state()))
+            },
+        )
+    }
+}
+
+impl_display_as_to_text!(<'g> ConstArray<'g>);
+
+impl<'g> ToText<'g> for ConstArray<'g> {
+    fn to_text(&self, state: &mut ToTextState<'g, '_>) -> fmt::Result {
+        let mut iter = self.elements.iter().copied();
+        write!(state, "[")?;
+        let first = iter.next().expect("array must have non-zero size");
+        first.to_text(state)?;
+        for element in iter {
+            write!(state, ", ")?;
+            element.to_text(state)?;
+        }
+        write!(state, "]")
+    }
+}
+
+impl FromToTextListForm for ConstStruct<'_> {}
+
+impl<'g> FromText<'g> for ConstStruct<'g> {
+    type Parsed = Self;
+    fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
+        let fields = ListForm::CURLY_BRACES.parse_vec(state, Const::from_text)?;
+        Ok(ConstStruct::new(fields, state.global_state()))
+    }
+}
+
+impl_display_as_to_text!(<'g> ConstStruct<'g>);
+
+impl<'g> ToText<'g> for ConstStruct<'g> {
+    fn to_text(&self, state: &mut ToTextState<'g, '_>) -> fmt::Result {
+        ListForm::CURLY_BRACES.list_to_text(state, self.fields.iter().copied())
+    }
+}
+
 impl FromToTextListForm for Const<'_> {}
 
 impl<'g> FromText<'g> for Const<'g> {
@@ -545,7 +1012,25 @@ impl<'g> FromText<'g> for Const<'g> {
                 Const::Bool(bool::from_text(state)?)
             }
             TokenKind::Punct(Punctuation::LessThan) => {
-                Const::Vector(ConstVector::from_text(state)?)
+                // both the fixed-vector form `<e0, e1, ...>` and the scalable-vector form
+                // `<N x splat ELEM>` start with '<', so peek past it to tell them apart: only
+                // the scalable form has a bare (unsuffixed) integer immediately followed by `x`.
+                let is_scalable_vector = matches!(
+                    state.peek_token_nth(1)?.kind.integer(),
+                    Some(IntegerToken { suffix: None, .. })
+                ) && state.peek_token_nth(2)?.kind.keyword()
+                    == Some(Keyword::X);
+                if is_scalable_vector {
+                    Const::ScalableVector(ConstScalableVector::from_text(state)?)
+                } else {
+                    Const::Vector(ConstVector::from_text(state)?)
+                }
+            }
+            TokenKind::Punct(Punctuation::LSquareBracket) => {
+                Const::Array(ConstArray::from_text(state)?)
+            }
+            TokenKind::Punct(Punctuation::LCurlyBrace) => {
+                Const::Struct(ConstStruct::from_text(state)?)
             }
             TokenKind::Keyword(Keyword::Undef) => {
                 state.parse_token()?;
@@ -559,7 +1044,6 @@ impl<'g> FromText<'g> for Const<'g> {
                 state.parse_token()?;
                 Const::Function(FunctionRef::from_text(state)?)
             }
-            // FIXME: add scalable vectors
             _ => state.error_at_peek_token("missing constant")?.into(),
         };
         Ok(retval.intern(state.global_state()))
@@ -575,6 +1059,9 @@ impl<'g> ToText<'g> for Const<'g> {
             Const::Float(v) => v.to_text(state),
             Const::Bool(v) => v.to_text(state),
             Const::Vector(v) => v.to_text(state),
+            Const::ScalableVector(v) => v.to_text(state),
+            Const::Array(v) => v.to_text(state),
+            Const::Struct(v) => v.to_text(state),
             Const::Undef(ty) => {
                 write!(state, "undef ")?;
                 ty.to_text(state)
@@ -630,14 +1117,54 @@ mod tests {
             "0xFFFFFFFFFFFFFFFFi64",
             0xFFFF_FFFF_FFFF_FFFFu64
         );
-        test_const!(global_state, "f16 0xF000", Float16(0xF000));
-        test_const!(global_state, "f32 0xFF000000", Float32(0xFF00_0000));
-        test_const!(global_state, "rf32 0xFF000000", RelaxedFloat32(0xFF00_0000));
+        test_const!(global_state, "f16 0xF000", Float16(0xF000), "f16 -8192");
+        test_const!(
+            global_state,
+            "f32 0xFF000000",
+            Float32(0xFF00_0000),
+            "f32 -170141180000000000000000000000000000000"
+        );
+        test_const!(
+            global_state,
+            "rf32 0xFF000000",
+            RelaxedFloat32(0xFF00_0000),
+            "rf32 -170141180000000000000000000000000000000"
+        );
         test_const!(
             global_state,
             "f64 0xFF00000000000000",
-            Float64(0xFF00_0000_0000_0000)
+            Float64(0xFF00_0000_0000_0000),
+            "f64 -5486124068793689000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
         );
+        // `NaN`'s bit pattern (and every other non-finite value) round-trips through its hex
+        // form, since there's no finite decimal value to print instead
+        test_const!(
+            global_state,
+            "f32 0x7FC00000",
+            Float32(0x7FC0_0000),
+            "f32 0x7FC00000"
+        );
+        test_const!(global_state, "f16 inf", Float16(0x7C00), "f16 0x7C00");
+        test_const!(
+            global_state,
+            "f32 -inf",
+            Float32(0xFF80_0000),
+            "f32 0xFF800000"
+        );
+        test_const!(
+            global_state,
+            "f64 nan",
+            Float64(0x7FF8_0000_0000_0000),
+            "f64 0x7FF8000000000000"
+        );
+        test_const!(global_state, "f32 1.5", Float32(0x3FC0_0000), "f32 1.5");
+        test_const!(
+            global_state,
+            "f64 -2.5e1",
+            Float64((-25.0f64).to_bits()),
+            "f64 -25"
+        );
+        test_const!(global_state, "f16 0.5", Float16(0x3800), "f16 0.5");
         test_const!(
             global_state,
             "<0x1i8>",
@@ -653,6 +1180,42 @@ mod tests {
             "<0x1i8, 0x2i8, 0x3i8, 0x4i8>",
             ConstVector::new(&[1u8, 2, 3, 4], &global_state)
         );
+        test_const!(
+            global_state,
+            "<4 x splat 0x1i8>",
+            ConstScalableVector::new(1u8, 4, &global_state)
+        );
+        test_const!(
+            global_state,
+            "<1 x splat f32 1.5>",
+            ConstScalableVector::new(Float32(0x3FC0_0000), 1, &global_state)
+        );
+        test_const!(
+            global_state,
+            "[0x1i8]",
+            ConstArray::new(&[1u8], &global_state)
+        );
+        test_const!(
+            global_state,
+            "[0x1i8, 0x2i8]",
+            ConstArray::new(&[1u8, 2u8], &global_state)
+        );
+        test_const!(
+            global_state,
+            "{0x1i8, f32 1.5}",
+            ConstStruct::new(
+                vec![
+                    1u8.intern(&global_state),
+                    Float32(0x3FC0_0000).intern(&global_state)
+                ],
+                &global_state
+            )
+        );
+        test_const!(
+            global_state,
+            "{}",
+            ConstStruct::new(Vec::<Interned<'_, Const<'_>>>::new(), &global_state)
+        );
         test_const!(
             global_state,
             "undef i8",
@@ -675,4 +1238,35 @@ mod tests {
         );
         // TODO: test Const::Function
     }
+
+    #[test]
+    fn test_const_vector_helpers() {
+        let global_state = GlobalState::new();
+        let v = ConstVector::splat(1u8, 3, &global_state);
+        assert_eq!(v, ConstVector::new(&[1u8, 1, 1], &global_state));
+        assert_eq!(v.get(0), 1u8.intern(&global_state));
+        assert_eq!(v.get(2), 1u8.intern(&global_state));
+        let mapped = v.map(&global_state, |element| match element.get() {
+            Const::Integer(ConstInteger::Int8(v)) => v + 1,
+            _ => unreachable!(),
+        });
+        assert_eq!(mapped, ConstVector::new(&[2u8, 2, 2], &global_state));
+        let zipped = v.zip_map(&mapped, &global_state, |a, b| match (a.get(), b.get()) {
+            (Const::Integer(ConstInteger::Int8(a)), Const::Integer(ConstInteger::Int8(b))) => a + b,
+            _ => unreachable!(),
+        });
+        assert_eq!(zipped, ConstVector::new(&[3u8, 3, 3], &global_state));
+    }
+
+    #[test]
+    fn test_const_integer_float_bitcast_round_trip() {
+        assert_eq!(
+            ConstInteger::Int32(0x3FC0_0000)
+                .bitcast_to_float()
+                .unwrap()
+                .bitcast_to_integer(),
+            ConstInteger::Int32(0x3FC0_0000)
+        );
+        assert!(ConstInteger::Int8(0).bitcast_to_float().is_err());
+    }
 }
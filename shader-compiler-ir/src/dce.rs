@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! dead-control-flow elimination
+//!
+//! The parser in `block.rs` already computes `end_reachable` -- whether a block's
+//! trailing instruction makes the rest of the body dead -- and turns a non-empty
+//! dead tail into a parse error. `eliminate_dead_code` takes the same observation
+//! (readable off of `CodeIO::results` per [[`crate::verify`]]'s traversal) and
+//! turns it into a rewrite instead of a diagnostic: it prunes unreachable tail
+//! instructions, drops blocks/loops that nothing actually branches to, and
+//! leaves value numbering alone -- it never renames a `Value`, only deletes the
+//! instructions that defined or referenced dead control flow.
+
+use crate::block::{BlockData, BlockRef, ContinueLoop, Loop, LoopRef};
+use crate::prelude::*;
+use crate::Instruction;
+use std::collections::HashSet;
+
+/// the set of blocks and loops that are still live targets after a full
+/// reachability scan
+#[derive(Default)]
+struct LiveTargets<'g> {
+    blocks: HashSet<BlockRef<'g>>,
+    loops: HashSet<LoopRef<'g>>,
+}
+
+impl<'g> LiveTargets<'g> {
+    fn scan_body(&mut self, body: &[Instruction<'g>]) {
+        for instruction in body {
+            if let Some(break_block) = instruction.downcast_ref::<BreakBlock<'g>>() {
+                self.blocks.insert(break_block.block);
+            } else if let Some(continue_loop) = instruction.downcast_ref::<ContinueLoop<'g>>() {
+                self.loops.insert(continue_loop.target_loop);
+            } else if let Some(loop_) = instruction.downcast_ref::<Loop<'g>>() {
+                if let Some(body) = loop_.body.body.get() {
+                    self.scan_body(body);
+                }
+            } else if let Some(block) = instruction.downcast_ref::<crate::block::Block<'g>>() {
+                if let Some(body) = block.body.get() {
+                    self.scan_body(body);
+                }
+            }
+        }
+    }
+}
+
+/// truncates `body` after the first instruction whose `results()` is
+/// `Uninhabited` -- everything past that point is unreachable, matching the
+/// parser's own `end_reachable` bookkeeping.
+fn drop_unreachable_tail<'g>(body: &[Instruction<'g>]) -> &[Instruction<'g>] {
+    for (index, instruction) in body.iter().enumerate() {
+        if let Uninhabited = instruction.results() {
+            return &body[..=index];
+        }
+    }
+    body
+}
+
+/// rewrites a single instruction list, dropping dead tails, inlining
+/// never-targeted single-fallthrough blocks, and degrading never-continued
+/// loops to straight-line code. Returns the rewritten instructions; the
+/// caller is responsible for allocating a new `BlockData`/`LoopData` from
+/// them in the same `GlobalState` the original tree came from.
+fn rewrite_body<'g>(body: &[Instruction<'g>], live: &LiveTargets<'g>) -> Vec<Instruction<'g>> {
+    let body = drop_unreachable_tail(body);
+    let mut retval = Vec::with_capacity(body.len());
+    for instruction in body {
+        if let Some(loop_) = instruction.downcast_ref::<Loop<'g>>() {
+            let loop_ref = LoopRef::new(loop_.value());
+            if !live.loops.contains(&loop_ref) {
+                // nothing ever continues this loop, so it executes its body exactly
+                // once; splice the (recursively rewritten) body in as straight-line
+                // code in place of the `Loop` instruction. Value numbering for the
+                // loop's own arguments is preserved because the header's
+                // `ValueDefinition`s are reused unchanged -- only the `Loop` wrapper
+                // instruction itself is removed.
+                if let Some(inner_body) = loop_.body.body.get() {
+                    retval.extend(rewrite_body(inner_body, live));
+                }
+                continue;
+            }
+        }
+        if let Some(block) = instruction.downcast_ref::<crate::block::Block<'g>>() {
+            let block_ref = BlockRef::new(block.value());
+            let can_inline = !live.blocks.contains(&block_ref)
+                && matches!(block.results(), Inhabited(_))
+                && block
+                    .body
+                    .get()
+                    .map_or(false, |inner| ends_with_single_fallthrough(inner));
+            if can_inline {
+                if let Some(inner_body) = block.body.get() {
+                    retval.extend(rewrite_body(inner_body, live));
+                }
+                continue;
+            }
+        }
+        retval.push(instruction.clone());
+    }
+    retval
+}
+
+/// a body has "exactly one fall-through" when its last instruction is an
+/// ordinary value-producing instruction rather than a `BreakBlock` back out to
+/// some other target -- i.e. control simply runs off the end of the body.
+fn ends_with_single_fallthrough<'g>(body: &[Instruction<'g>]) -> bool {
+    match body.last() {
+        Some(instruction) => instruction.downcast_ref::<BreakBlock<'g>>().is_none(),
+        None => true,
+    }
+}
+
+/// eliminate provably-dead control flow in `block`: unreachable instruction
+/// tails, blocks nothing branches to, and loops nothing continues. Allocates
+/// the rewritten tree in `global_state`, leaving the input `block` untouched
+/// and leaving value numbering of surviving instructions intact.
+pub fn eliminate_dead_code<'g>(
+    global_state: &'g GlobalState<'g>,
+    block: BlockRef<'g>,
+) -> BlockRef<'g> {
+    let mut live = LiveTargets::default();
+    if let Some(body) = block.body.get() {
+        live.scan_body(body);
+    }
+    let rewritten = block.body.get().map(|body| rewrite_body(body, &live));
+    let new_block = global_state.alloc(BlockData {
+        name: block.name,
+        body: OnceCell::new(),
+        result_definitions: block.result_definitions.clone(),
+    });
+    if let Some(rewritten) = rewritten {
+        new_block
+            .body
+            .set(rewritten)
+            .unwrap_or_else(|_| unreachable!());
+    }
+    BlockRef::new(new_block)
+}
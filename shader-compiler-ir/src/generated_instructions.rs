@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+// @generated by `cargo xtask codegen` from xtask/src/codegen/table.rs.
+// Do not edit by hand -- edit the table and regenerate instead.
+
+/// `ToText` body generated for `BinaryALUInstruction`
+pub fn to_text_add<'g>(value: &BinaryALUInstruction<'g>, state: &mut ToTextState<'g, '_>) -> std::fmt::Result {
+    write!(state, "add ")?;
+    value.lhs.to_text(state)?;
+    value.rhs.to_text(state)?;
+    write!(state, " -> ")?;
+    value.result.to_text(state)?;
+    Ok(())
+}
+
+/// `FromText` body generated for `BinaryALUInstruction`, called once the `add` mnemonic is peeked
+pub fn from_text_add<'g>(state: &mut FromTextState<'g, '_>) -> Result<BinaryALUInstruction<'g>, FromTextError> {
+    let lhs = ValueUse::from_text(state)?;
+    let rhs = ValueUse::from_text(state)?;
+    state.parse_punct_token_or_error(Punctuation::Arrow, "missing arrow: '->'")?;
+    let result = ValueDefinition::from_text(state)?;
+    Ok(BinaryALUInstruction { lhs, rhs, result, })
+}
+
+/// `ToText` body generated for `BranchInstruction`
+pub fn to_text_branch<'g>(value: &BranchInstruction<'g>, state: &mut ToTextState<'g, '_>) -> std::fmt::Result {
+    write!(state, "branch ")?;
+    value.variable.to_text(state)?;
+    Ok(())
+}
+
+/// `FromText` body generated for `BranchInstruction`, called once the `branch` mnemonic is peeked
+pub fn from_text_branch<'g>(state: &mut FromTextState<'g, '_>) -> Result<BranchInstruction<'g>, FromTextError> {
+    let variable = ValueUse::from_text(state)?;
+    Ok(BranchInstruction { variable, })
+}
+
+/// `ToText` body generated for `BreakBlock`
+pub fn to_text_break<'g>(value: &BreakBlock<'g>, state: &mut ToTextState<'g, '_>) -> std::fmt::Result {
+    write!(state, "break ")?;
+    value.block.to_text(state)?;
+    value.block_results.to_text(state)?;
+    Ok(())
+}
+
+/// `FromText` body generated for `BreakBlock`, called once the `break` mnemonic is peeked
+pub fn from_text_break<'g>(state: &mut FromTextState<'g, '_>) -> Result<BreakBlock<'g>, FromTextError> {
+    let block = BlockRef::from_text(state)?;
+    let block_results = Vec::<ValueUse>::from_text(state)?;
+    Ok(BreakBlock { block, block_results, })
+}
+
+/// `ToText` body generated for `ContinueLoop`
+pub fn to_text_continue<'g>(value: &ContinueLoop<'g>, state: &mut ToTextState<'g, '_>) -> std::fmt::Result {
+    write!(state, "continue ")?;
+    value.target_loop.to_text(state)?;
+    value.loop_arguments.to_text(state)?;
+    Ok(())
+}
+
+/// `FromText` body generated for `ContinueLoop`, called once the `continue` mnemonic is peeked
+pub fn from_text_continue<'g>(state: &mut FromTextState<'g, '_>) -> Result<ContinueLoop<'g>, FromTextError> {
+    let target_loop = LoopRef::from_text(state)?;
+    let loop_arguments = Vec::<ValueUse>::from_text(state)?;
+    Ok(ContinueLoop { target_loop, loop_arguments, })
+}
+
+/// `ToText` body generated for `Loop`
+pub fn to_text_loop<'g>(value: &Loop<'g>, state: &mut ToTextState<'g, '_>) -> std::fmt::Result {
+    write!(state, "loop ")?;
+    value.arguments.to_text(state)?;
+    Ok(())
+}
+
+/// `FromText` body generated for `Loop`, called once the `loop` mnemonic is peeked
+pub fn from_text_loop<'g>(state: &mut FromTextState<'g, '_>) -> Result<Loop<'g>, FromTextError> {
+    let arguments = Vec::<ValueUse>::from_text(state)?;
+    Ok(Loop { arguments, })
+}
+
+#[cfg(test)]
+mod generated_round_trip_tests {
+    use super::*;
+
+    #[test]
+    fn generated_round_trip_add() {
+        let global_state = GlobalState::new();
+        let global_state = &global_state;
+        let lhs = ValueUse::from_const(1u32, "", global_state);
+        let rhs = ValueUse::from_const(2u32, "", global_state);
+        let result = ValueDefinition::new(IntegerType::Int32, "result", global_state);
+        let value = BinaryALUInstruction { lhs, rhs, result, };
+        let text = value.display().to_string();
+        let parsed = BinaryALUInstruction::parse("", &text, global_state).unwrap();
+        assert_eq!(text, parsed.display().to_string());
+    }
+
+    #[test]
+    fn generated_round_trip_branch() {
+        let global_state = GlobalState::new();
+        let global_state = &global_state;
+        let variable = ValueUse::from_const(1u32, "", global_state);
+        let value = BranchInstruction { variable, };
+        let text = value.display().to_string();
+        let parsed = BranchInstruction::parse("", &text, global_state).unwrap();
+        assert_eq!(text, parsed.display().to_string());
+    }
+
+    #[test]
+    fn generated_round_trip_break() {
+        let global_state = GlobalState::new();
+        let global_state = &global_state;
+        let target_block = Block::without_body("target", Inhabited(vec![]), global_state);
+        let block = BlockRef::new(target_block.value());
+        let block_results: Vec<ValueUse> = vec![];
+        let value = BreakBlock { block, block_results, };
+        let text = value.display().to_string();
+        let parsed = BreakBlock::parse("", &text, global_state).unwrap();
+        assert_eq!(text, parsed.display().to_string());
+    }
+
+    #[test]
+    fn generated_round_trip_continue() {
+        let global_state = GlobalState::new();
+        let global_state = &global_state;
+        let target_block = Block::without_body("target", Inhabited(vec![]), global_state);
+        let target_loop = Loop::new("target_loop", vec![], vec![], target_block, global_state);
+        let target_loop = LoopRef::new(target_loop.value());
+        let loop_arguments: Vec<ValueUse> = vec![];
+        let value = ContinueLoop { target_loop, loop_arguments, };
+        let text = value.display().to_string();
+        let parsed = ContinueLoop::parse("", &text, global_state).unwrap();
+        assert_eq!(text, parsed.display().to_string());
+    }
+
+    #[test]
+    fn generated_round_trip_loop() {
+        let global_state = GlobalState::new();
+        let global_state = &global_state;
+        let arguments: Vec<ValueUse> = vec![];
+        let value = Loop { arguments, };
+        let text = value.display().to_string();
+        let parsed = Loop::parse("", &text, global_state).unwrap();
+        assert_eq!(text, parsed.display().to_string());
+    }
+
+}
@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! a map from a single, shared `BytePos` address space to the several
+//! source files a multi-file `from_text` parse may pull in (an
+//! included/imported module, for instance). `TextLocation`/`TextSpan`
+//! stay scoped to one `FromTextSourceCode` each -- changing that would
+//! ripple through every `from_text` parser in this crate -- but a
+//! `SourceMap` lets the few places that need to compare or report
+//! locations *across* files (error reporting chief among them) do so
+//! without tripping the "same source" assertions those types still
+//! enforce internally.
+//!
+//! modeled after rustc's `source_map::SourceMap`: each registered file
+//! is assigned a non-overlapping range of `BytePos`, found by
+//! binary-searching the sorted file start positions.
+
+use crate::text::{FromTextErrorLocation, FromTextSourceCode, TextLocation, TextSpan};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// a byte position in a [`SourceMap`]'s shared address space, rather than a single file's
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct BytePos(pub usize);
+
+struct RegisteredFile<'a> {
+    start: usize,
+    source_code: FromTextSourceCode<'a>,
+}
+
+/// owns the source files registered for a multi-file `from_text` parse, assigning each a non-overlapping range in a shared `BytePos` address space
+#[derive(Default)]
+pub struct SourceMap<'a> {
+    files: Vec<RegisteredFile<'a>>,
+    next_start: usize,
+}
+
+impl<'a> SourceMap<'a> {
+    /// create an empty `SourceMap`
+    pub fn new() -> Self {
+        Self {
+            files: Vec::new(),
+            next_start: 0,
+        }
+    }
+
+    /// registers `text` as a new file named `file_name`, returning the `BytePos` of its first byte
+    pub fn add_file(&mut self, file_name: &'a str, text: &'a str) -> BytePos {
+        let start = self.next_start;
+        // the `+ 1` keeps one file's end position and the next file's
+        // start position from ever comparing equal, so a `BytePos` one
+        // past the last byte of a file still resolves to that file.
+        self.next_start = start + text.len() + 1;
+        self.files.push(RegisteredFile {
+            start,
+            source_code: FromTextSourceCode::new(file_name, text),
+        });
+        BytePos(start)
+    }
+
+    fn file_index_at(&self, pos: BytePos) -> usize {
+        match self.files.binary_search_by_key(&pos.0, |file| file.start) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        }
+    }
+
+    /// the registered file that `pos` falls within
+    pub fn file_at(&self, pos: BytePos) -> &FromTextSourceCode<'a> {
+        &self.files[self.file_index_at(pos)].source_code
+    }
+
+    /// the `TextLocation` for the global position `pos`, into whichever registered file it falls within
+    pub fn location_at(&'a self, pos: BytePos) -> TextLocation<'a> {
+        let file = &self.files[self.file_index_at(pos)];
+        TextLocation::new(pos.0 - file.start, &file.source_code)
+    }
+
+    /// resolves `pos` to a human-readable `FromTextErrorLocation` -- the file name, line, and column of whichever registered file `pos` falls within
+    pub fn resolve(&'a self, pos: BytePos) -> FromTextErrorLocation {
+        self.location_at(pos).into()
+    }
+
+    /// the global `BytePos` of `location`, the inverse of [`location_at`](Self::location_at)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `location`'s source code was not registered in this map via [`add_file`](Self::add_file).
+    pub fn byte_pos_of(&self, location: TextLocation<'a>) -> BytePos {
+        for file in &self.files {
+            if core::ptr::eq(&file.source_code, location.source_code()) {
+                return BytePos(file.start + location.byte_index());
+            }
+        }
+        panic!("TextLocation's source code was not registered in this SourceMap");
+    }
+
+    /// the global `BytePos` range of `span`, the cross-file counterpart to `span.byte_indexes()`
+    pub fn byte_pos_range_of(&self, span: TextSpan<'a>) -> Range<BytePos> {
+        self.byte_pos_of(span.start())..self.byte_pos_of(span.end())
+    }
+}
@@ -0,0 +1,252 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! SPIR-V module emission, parallel to the binary IR format in [`crate::binary`].
+//!
+//! A full backend needs to lower `Instruction`/`InstructionData` (structured
+//! control flow to `OpLoopMerge`/`OpSelectionMerge`/`OpBranchConditional`, ALU
+//! ops to their `Op*` equivalents, `ValueDefinition` to allocated result ids,
+//! and `Location` to `OpLine`) plus an entry point's interface list of
+//! `Global`s. None of `InstructionData`, `BinaryALUInstruction`, or a
+//! `Module`/`GlobalState`-owned list of globals exist
+//! in this tree yet -- the same gap [`crate::block::Global`] was added
+//! against instead of extending `InstructionData::Load`/`Store` -- so this
+//! module covers only the piece that's expressible without them: walking
+//! interned [`Type`]s into `OpType*` declarations, word-encoded the way the
+//! rest of a SPIR-V module body would be.
+//!
+//! Ids are allocated the same way `ToBinaryState` allocates dense block/loop
+//! ids: the first time something is emitted, not up front, and a
+//! `HashMap` keyed by the already-interned `Type` gives id reuse for free --
+//! two equal types are the same `Interned` value, so no separate dedup pass
+//! is needed.
+
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+/// SPIR-V magic number (see the SPIR-V spec, section 2.3 "Physical Layout of a SPIR-V Module and Instructions")
+const MAGIC_NUMBER: u32 = 0x0723_0203;
+/// SPIR-V version 1.0, encoded as `0 | major << 16 | minor << 8 | 0`
+const VERSION_1_0: u32 = 0x0001_0000;
+/// generator magic number; `0` means "no registered generator"
+const GENERATOR_MAGIC_NUMBER: u32 = 0;
+
+/// a SPIR-V `<id>` result id
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SpirvId(u32);
+
+impl SpirvId {
+    /// the numeric value of this id, as it appears in the emitted words
+    pub fn get(self) -> u32 {
+        self.0
+    }
+}
+
+/// a `Type` that can't be translated to a SPIR-V `OpType*` declaration
+#[derive(Clone, Debug)]
+pub enum EmitTypeError<'g> {
+    /// `OpaqueType` has no variants yet, so reaching one is a logic error, not a real input
+    Opaque,
+    /// SPIR-V has no core-spec equivalent of a scalable vector
+    ScalableVectorUnsupported(VectorType<'g>),
+    /// `PointerType` doesn't carry a storage class, so it can't be assigned
+    /// one of SPIR-V's required `OpTypePointer` storage classes; see
+    /// [`TypeEmitter::emit_pointer_type`]
+    PointerStorageClassUnknown(PointerType<'g>),
+}
+
+impl<'g> fmt::Display for EmitTypeError<'g> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmitTypeError::Opaque => write!(f, "can't emit an opaque type"),
+            EmitTypeError::ScalableVectorUnsupported(_) => {
+                write!(f, "SPIR-V has no equivalent of a scalable vector")
+            }
+            EmitTypeError::PointerStorageClassUnknown(_) => write!(
+                f,
+                "can't emit a pointer type without knowing its storage class"
+            ),
+        }
+    }
+}
+
+impl<'g> std::error::Error for EmitTypeError<'g> {}
+
+/// word-encodes a single SPIR-V instruction (a length-and-opcode header word
+/// followed by its operand words) onto the end of `words`
+fn push_instruction(words: &mut Vec<u32>, opcode: u16, operands: &[u32]) {
+    let word_count = 1 + operands.len() as u32;
+    words.push(word_count << 16 | u32::from(opcode));
+    words.extend_from_slice(operands);
+}
+
+/// walks interned [`Type`]s into `OpType*` words, allocating each a fresh
+/// [`SpirvId`] the first time it's emitted
+pub struct TypeEmitter<'g> {
+    global_state: &'g GlobalState<'g>,
+    ids: HashMap<Interned<'g, Type<'g>>, SpirvId>,
+    /// `OpType*`/`OpConstant` words emitted so far, in definition order
+    words: Vec<u32>,
+    next_id: u32,
+}
+
+impl<'g> TypeEmitter<'g> {
+    /// create an emitter with no types emitted yet
+    pub fn new(global_state: &'g GlobalState<'g>) -> Self {
+        // id 0 isn't a valid SPIR-V id, so the first allocated id is 1
+        TypeEmitter {
+            global_state,
+            ids: HashMap::new(),
+            words: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    fn alloc_id(&mut self) -> SpirvId {
+        let id = SpirvId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// emit an `OpConstant` holding a 32-bit unsigned integer, used for
+    /// `OpTypeArray`'s length operand
+    fn emit_u32_constant(&mut self, value: u32) -> Result<SpirvId, EmitTypeError<'g>> {
+        let int32_type = IntegerType::Int32.intern(self.global_state);
+        let type_id = self.emit_type(int32_type)?;
+        let id = self.alloc_id();
+        push_instruction(
+            &mut self.words,
+            43, /* OpConstant */
+            &[type_id.0, id.0, value],
+        );
+        Ok(id)
+    }
+
+    /// emit `type`'s `OpType*` declaration (and those of any types it
+    /// contains), returning the id of an identical previous emission instead
+    /// of a new one if `type` was already emitted
+    pub fn emit_type(&mut self, ty: Interned<'g, Type<'g>>) -> Result<SpirvId, EmitTypeError<'g>> {
+        if let Some(&id) = self.ids.get(&ty) {
+            return Ok(id);
+        }
+        let id = match &*ty {
+            Type::Integer(integer_type) => self.emit_integer_type(*integer_type),
+            Type::Float(float_type) => self.emit_float_type(*float_type),
+            Type::Bool(BoolType) => self.emit_simple_type(20 /* OpTypeBool */),
+            Type::Pointer(pointer_type) => self.emit_pointer_type(pointer_type.clone())?,
+            Type::Vector(vector_type) => self.emit_vector_type(vector_type.clone())?,
+            Type::Array(array_type) => self.emit_array_type(array_type.clone())?,
+            Type::Struct(struct_type) => self.emit_struct_type(struct_type.clone())?,
+            Type::Opaque(_) => return Err(EmitTypeError::Opaque),
+        };
+        self.ids.insert(ty, id);
+        Ok(id)
+    }
+
+    fn emit_simple_type(&mut self, opcode: u16) -> SpirvId {
+        let id = self.alloc_id();
+        push_instruction(&mut self.words, opcode, &[id.0]);
+        id
+    }
+
+    fn emit_integer_type(&mut self, integer_type: IntegerType) -> SpirvId {
+        let width = match integer_type {
+            IntegerType::Int8 => 8,
+            IntegerType::Int16 => 16,
+            IntegerType::Int32 | IntegerType::RelaxedInt32 => 32,
+            IntegerType::Int64 => 64,
+        };
+        let id = self.alloc_id();
+        // signedness `0`: this IR's `IntegerType` doesn't distinguish signed
+        // from unsigned, matching how its ALU ops pick sign-sensitive
+        // opcodes (`OpSDiv` vs `OpUDiv`) rather than the operand type
+        push_instruction(&mut self.words, 21 /* OpTypeInt */, &[id.0, width, 0]);
+        id
+    }
+
+    fn emit_float_type(&mut self, float_type: FloatType) -> SpirvId {
+        let width = match float_type {
+            FloatType::Float16 => 16,
+            FloatType::Float32 | FloatType::RelaxedFloat32 => 32,
+            FloatType::Float64 => 64,
+        };
+        let id = self.alloc_id();
+        push_instruction(&mut self.words, 22 /* OpTypeFloat */, &[id.0, width]);
+        id
+    }
+
+    fn emit_pointer_type(
+        &mut self,
+        pointer_type: PointerType<'g>,
+    ) -> Result<SpirvId, EmitTypeError<'g>> {
+        // `PointerType` has no storage-class field to translate, so there's
+        // no sound choice to make here; reporting it lets a caller decide
+        // whether a fixed default (e.g. always `Function`) is acceptable
+        // for its use case rather than this module silently picking one.
+        Err(EmitTypeError::PointerStorageClassUnknown(pointer_type))
+    }
+
+    fn emit_vector_type(
+        &mut self,
+        vector_type: VectorType<'g>,
+    ) -> Result<SpirvId, EmitTypeError<'g>> {
+        if vector_type.scalable {
+            return Err(EmitTypeError::ScalableVectorUnsupported(vector_type));
+        }
+        let component_type_id = self.emit_type(vector_type.element)?;
+        let id = self.alloc_id();
+        push_instruction(
+            &mut self.words,
+            23, /* OpTypeVector */
+            &[id.0, component_type_id.0, vector_type.len as u32],
+        );
+        Ok(id)
+    }
+
+    fn emit_array_type(&mut self, array_type: ArrayType<'g>) -> Result<SpirvId, EmitTypeError<'g>> {
+        let element_type_id = self.emit_type(array_type.element)?;
+        let length_id = self.emit_u32_constant(array_type.len as u32)?;
+        let id = self.alloc_id();
+        push_instruction(
+            &mut self.words,
+            28, /* OpTypeArray */
+            &[id.0, element_type_id.0, length_id.0],
+        );
+        Ok(id)
+    }
+
+    fn emit_struct_type(
+        &mut self,
+        struct_type: StructType<'g>,
+    ) -> Result<SpirvId, EmitTypeError<'g>> {
+        let mut member_type_ids = Vec::with_capacity(struct_type.members.len());
+        for member in &struct_type.members {
+            member_type_ids.push(self.emit_type(*member)?.0);
+        }
+        let id = self.alloc_id();
+        let mut operands = Vec::with_capacity(1 + member_type_ids.len());
+        operands.push(id.0);
+        operands.extend(member_type_ids);
+        push_instruction(&mut self.words, 30 /* OpTypeStruct */, &operands);
+        Ok(id)
+    }
+
+    /// assemble a complete, header-prefixed module out of the `OpType*`/`OpConstant`
+    /// words emitted so far
+    ///
+    /// the result is only the type/constant section of a real shader module --
+    /// see the module-level docs for what's still missing (functions, control
+    /// flow, and an entry point)
+    pub fn into_module_words(self) -> Vec<u32> {
+        let bound = self.next_id;
+        let mut words = Vec::with_capacity(5 + self.words.len());
+        words.push(MAGIC_NUMBER);
+        words.push(VERSION_1_0);
+        words.push(GENERATOR_MAGIC_NUMBER);
+        words.push(bound);
+        words.push(0); // schema, reserved, must be 0
+        words.extend(self.words);
+        words
+    }
+}
@@ -5,12 +5,14 @@
 
 use crate::{prelude::*, IdRef, StructType};
 use alloc::{
+    boxed::Box,
     string::{String, ToString},
     vec::Vec,
 };
 use arrayvec::{Array, ArrayVec};
 use core::{
     borrow::{Borrow, BorrowMut},
+    cell::Cell,
     fmt,
     marker::PhantomData,
     mem,
@@ -113,7 +115,13 @@ macro_rules! impl_struct_with_default_from_to_text {
                                         $retval.$member = <$member_ty>::from_text($state)?;
                                     }
                                 )+
-                                _ => $state.error_at_peek_token("unknown field name")?.into(),
+                                _ => {
+                                    let message = match crate::text::suggest_closest(ident, [$(stringify!($member)),+].iter().copied()) {
+                                        Some(suggestion) => format!("unknown field name; help: did you mean `{}`?", suggestion),
+                                        None => "unknown field name".to_string(),
+                                    };
+                                    $state.error_at_peek_token(message)?.into()
+                                }
                             }
                             $state.parse_punct_token_or_error(crate::text::Punctuation::Comma, "missing comma (',') after field value")?;
                         }
@@ -140,6 +148,92 @@ macro_rules! impl_struct_with_default_from_to_text {
     };
 }
 
+const USIZE_BYTES: usize = mem::size_of::<usize>();
+
+/// an all-`byte` word the width of a native `usize`, used by [`analyze_source_file`]'s
+/// word-at-a-time scan
+const fn splat(byte: u8) -> usize {
+    let mut result = 0usize;
+    let mut remaining = USIZE_BYTES;
+    while remaining > 0 {
+        result = (result << 8) | byte as usize;
+        remaining -= 1;
+    }
+    result
+}
+
+const LOW_BITS_OF_EACH_BYTE: usize = splat(0x01);
+const HIGH_BIT_OF_EACH_BYTE: usize = splat(0x80);
+const NEWLINE_WORD: usize = splat(b'\n');
+const TAB_WORD: usize = splat(b'\t');
+
+/// true if any byte of `word` is `0`, using the standard "find a zero byte in a word"
+/// bit-trick rather than comparing each byte individually
+const fn has_zero_byte(word: usize) -> bool {
+    word.wrapping_sub(LOW_BITS_OF_EACH_BYTE) & !word & HIGH_BIT_OF_EACH_BYTE != 0
+}
+
+/// the result of a single-pass scan over a source file's text: everything the `FromText`
+/// diagnostics machinery needs to know about where lines, tabs, and non-ASCII bytes are,
+/// gathered in one pass so later queries (line lookup, column computation) never need to
+/// re-scan the source text byte by byte.
+#[derive(Debug)]
+struct SourceFileAnalysis {
+    /// byte indexes of line starts, always starting with 0
+    line_start_byte_indexes: Vec<usize>,
+    /// byte indexes of every byte that is not plain 7-bit ASCII, in ascending order
+    non_ascii_byte_indexes: Vec<usize>,
+    /// whether `\t` appears anywhere in the file
+    has_tabs: bool,
+}
+
+/// scans `text` for line starts, tabs, and non-ASCII bytes in one pass, modeled on rustc's
+/// `analyze_source_file`: `usize`-sized chunks are checked all at once for "nothing of
+/// interest in this word" (no newline, no tab, all bytes `< 0x80`) via bit tricks, and only
+/// words that might contain one of those bytes are re-examined one byte at a time.
+fn analyze_source_file(text: &str) -> SourceFileAnalysis {
+    let bytes = text.as_bytes();
+    let mut line_start_byte_indexes = vec![0];
+    let mut non_ascii_byte_indexes = Vec::new();
+    let mut has_tabs = false;
+    let mut record_byte = |index: usize, byte: u8| {
+        match byte {
+            b'\n' => line_start_byte_indexes.push(index + 1),
+            b'\t' => has_tabs = true,
+            _ => {}
+        }
+        if byte >= 0x80 {
+            non_ascii_byte_indexes.push(index);
+        }
+    };
+    let mut index = 0;
+    while index + USIZE_BYTES <= bytes.len() {
+        let word = usize::from_ne_bytes(
+            bytes[index..index + USIZE_BYTES]
+                .try_into()
+                .expect("slice has exactly USIZE_BYTES bytes"),
+        );
+        let nothing_of_interest = !has_zero_byte(word ^ NEWLINE_WORD)
+            && !has_zero_byte(word ^ TAB_WORD)
+            && word & HIGH_BIT_OF_EACH_BYTE == 0;
+        if !nothing_of_interest {
+            for (offset, &byte) in bytes[index..index + USIZE_BYTES].iter().enumerate() {
+                record_byte(index + offset, byte);
+            }
+        }
+        index += USIZE_BYTES;
+    }
+    while index < bytes.len() {
+        record_byte(index, bytes[index]);
+        index += 1;
+    }
+    SourceFileAnalysis {
+        line_start_byte_indexes,
+        non_ascii_byte_indexes,
+        has_tabs,
+    }
+}
+
 /// the struct managing the source code for `FromText`.
 #[derive(Debug)]
 pub struct FromTextSourceCode<'a> {
@@ -147,7 +241,7 @@ pub struct FromTextSourceCode<'a> {
     pub file_name: &'a str,
     /// the source code
     pub text: &'a str,
-    line_start_byte_indexes: OnceCell<Vec<usize>>,
+    analysis: OnceCell<SourceFileAnalysis>,
 }
 
 impl<'a> FromTextSourceCode<'a> {
@@ -156,23 +250,28 @@ impl<'a> FromTextSourceCode<'a> {
         Self {
             file_name,
             text,
-            line_start_byte_indexes: OnceCell::new(),
+            analysis: OnceCell::new(),
         }
     }
+    fn analysis(&self) -> &SourceFileAnalysis {
+        self.analysis.get_or_init(|| analyze_source_file(self.text))
+    }
     /// byte indexes of line starts
     /// always starts with 0
     pub fn line_start_byte_indexes(&self) -> &[usize] {
-        self.line_start_byte_indexes.get_or_init(|| {
-            let mut line_start_byte_indexes = vec![0];
-            for (index, byte) in self.text.bytes().enumerate() {
-                if byte == b'\n' {
-                    // don't need to specifically check for "\r\n" since
-                    // line start still is right after '\n'
-                    line_start_byte_indexes.push(index + 1);
-                }
-            }
-            line_start_byte_indexes
-        })
+        &self.analysis().line_start_byte_indexes
+    }
+    /// true if `\t` appears anywhere in the source code
+    fn has_tabs(&self) -> bool {
+        self.analysis().has_tabs
+    }
+    /// true if any byte in `byte_range` is not plain 7-bit ASCII
+    fn has_non_ascii_in_range(&self, byte_range: Range<usize>) -> bool {
+        let non_ascii_byte_indexes = &self.analysis().non_ascii_byte_indexes;
+        let first_at_or_after_start = non_ascii_byte_indexes.partition_point(|&index| index < byte_range.start);
+        non_ascii_byte_indexes
+            .get(first_at_or_after_start)
+            .map_or(false, |&index| index < byte_range.end)
     }
     /// 0-based line number of the line containing byte_index
     pub fn line_index_of_containing_line(&self, byte_index: usize) -> usize {
@@ -184,6 +283,66 @@ impl<'a> FromTextSourceCode<'a> {
     }
 }
 
+/// a view of a [`FromTextSourceCode`] that remembers the last line it resolved a byte index
+/// to, so repeated [`line_index_of_containing_line`](Self::line_index_of_containing_line)
+/// queries -- as batch error reporting or span dumps make, usually in increasing byte-index
+/// order -- skip `line_index_of_containing_line`'s binary search whenever the next query
+/// lands in the same line or the one right after it.
+///
+/// kept as a separate wrapper, rather than caching on `FromTextSourceCode` itself, so the
+/// common one-error-at-a-time parsing path stays exactly as zero-overhead as it is today.
+pub struct CachingSourceMapView<'a> {
+    source_code: &'a FromTextSourceCode<'a>,
+    // (line_index, line's start byte index, exclusive end byte index -- `usize::MAX` for the
+    // last line, so any byte index at or past its start is recognized as still being on it)
+    cached_line: Cell<Option<(usize, usize, usize)>>,
+}
+
+impl<'a> CachingSourceMapView<'a> {
+    /// create a new `CachingSourceMapView` over `source_code`, with nothing cached yet
+    pub fn new(source_code: &'a FromTextSourceCode<'a>) -> Self {
+        Self {
+            source_code,
+            cached_line: Cell::new(None),
+        }
+    }
+    /// the underlying source code
+    pub fn source_code(&self) -> &'a FromTextSourceCode<'a> {
+        self.source_code
+    }
+    fn line_range(&self, line_index: usize) -> (usize, usize) {
+        let line_start_byte_indexes = self.source_code.line_start_byte_indexes();
+        let start = line_start_byte_indexes[line_index];
+        let end = line_start_byte_indexes
+            .get(line_index + 1)
+            .copied()
+            .unwrap_or(usize::MAX);
+        (start, end)
+    }
+    /// equivalent to [`FromTextSourceCode::line_index_of_containing_line`], but checks the
+    /// last-resolved line and its successor before falling back to a binary search
+    pub fn line_index_of_containing_line(&self, byte_index: usize) -> usize {
+        if let Some((line_index, start, end)) = self.cached_line.get() {
+            if byte_index >= start && byte_index < end {
+                return line_index;
+            }
+            let next_line_index = line_index + 1;
+            if next_line_index < self.source_code.line_start_byte_indexes().len() {
+                let (next_start, next_end) = self.line_range(next_line_index);
+                if byte_index >= next_start && byte_index < next_end {
+                    self.cached_line
+                        .set(Some((next_line_index, next_start, next_end)));
+                    return next_line_index;
+                }
+            }
+        }
+        let line_index = self.source_code.line_index_of_containing_line(byte_index);
+        let (start, end) = self.line_range(line_index);
+        self.cached_line.set(Some((line_index, start, end)));
+        line_index
+    }
+}
+
 /// the location of an error produced by `FromText`
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct FromTextErrorLocation {
@@ -207,6 +366,79 @@ impl fmt::Display for FromTextErrorLocation {
     }
 }
 
+/// how confident a [`Suggestion`] is, mirroring rustc's own `Applicability`
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Applicability {
+    /// applying `replacement` is guaranteed to produce what the user meant; an editor or
+    /// `rustfix`-style tool can apply it automatically with no review
+    MachineApplicable,
+    /// `replacement` is a reasonable guess at what the user meant, but could be wrong; should
+    /// be shown to the user rather than applied automatically
+    MaybeIncorrect,
+}
+
+/// a fix-it: replacing the source text at `span` with `replacement` would address the
+/// diagnostic it's attached to
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Suggestion {
+    /// the byte range to replace
+    pub span: Range<usize>,
+    /// the text to replace `span` with
+    pub replacement: String,
+    /// how confident this suggestion is
+    pub applicability: Applicability,
+}
+
+/// how serious a [`FromTextError`] is
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Severity {
+    /// parsing cannot be considered successful while any `Error`-severity diagnostic was
+    /// collected -- see [`FromText::parse_collect_errors`]
+    Error,
+    /// informational; doesn't by itself prevent parsing from being considered successful
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// a builder for a multi-span [`FromTextError`], the `codespan`-style counterpart to
+/// [`FromTextState::error_at`]'s single-span diagnostics.
+///
+/// construct via [`FromTextState::diagnostic`], attach any number of labeled secondary spans
+/// with [`Self::with_secondary`] (e.g. the original definition's span for a "name first defined
+/// here" label on a duplicate-name error) and an optional closing note with [`Self::with_note`],
+/// then hand the result to [`FromTextState::error_at_diagnostic`].
+pub struct Diagnostic {
+    location: FromTextErrorLocation,
+    span: Range<usize>,
+    message: String,
+    suggestion: Option<Suggestion>,
+    secondary: Vec<(Range<usize>, String)>,
+    note: Option<String>,
+}
+
+impl Diagnostic {
+    /// attach a secondary labeled span, rendered after the primary span with `label` appended
+    /// inline after its final caret run (e.g. `"name first defined here"`)
+    pub fn with_secondary<L: ErrorByteRange>(mut self, location: L, label: impl ToString) -> Self {
+        self.secondary
+            .push((location.error_byte_range(), label.to_string()));
+        self
+    }
+    /// attach a closing note, for context that doesn't belong to any one span
+    pub fn with_note(mut self, note: impl ToString) -> Self {
+        self.note = Some(note.to_string());
+        self
+    }
+}
+
 /// an error produced by `FromText`
 #[derive(Clone, Debug)]
 pub struct FromTextError {
@@ -214,14 +446,156 @@ pub struct FromTextError {
     pub location: FromTextErrorLocation,
     /// the description of the error
     pub message: String,
+    /// the byte range `self` applies to, if known; used by [`render_snippet`](Self::render_snippet)
+    /// to underline the offending source text. `None` for the empty placeholder error
+    /// `error_at` returns while running in error-recovery mode.
+    pub span: Option<Range<usize>>,
+    /// how serious `self` is
+    pub severity: Severity,
+    /// a fix-it suggestion for `self`, if one is known
+    pub suggestion: Option<Suggestion>,
+    /// additional labeled spans beyond `self.span`, rendered by
+    /// [`render_snippet`](Self::render_snippet) after the primary span -- e.g. the original
+    /// definition's span for a "name first defined here" label on a duplicate-name error. Built
+    /// via [`FromTextState::diagnostic`]; empty for every plain [`FromTextState::error_at`].
+    pub secondary: Vec<(Range<usize>, String)>,
+    /// a closing note appended after every span, for context that doesn't belong to any one
+    /// span (e.g. "identifiers are compared case-sensitively")
+    pub note: Option<String>,
 }
 
 impl fmt::Display for FromTextError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}: error: {}", self.location, self.message)
+        write!(f, "{}: {}: {}", self.location, self.severity, self.message)
+    }
+}
+
+impl FromTextError {
+    /// append the source line(s) covering `span` to `result`, each followed by a run of `^`
+    /// carets underlining it, one caret run per line for spans that cross line boundaries; if
+    /// `label` is non-empty it's appended after the final caret run. Shared by
+    /// [`render_snippet`](Self::render_snippet) for both the primary span and every secondary
+    /// span.
+    fn render_span(result: &mut String, source: &FromTextSourceCode, span: &Range<usize>, label: &str) {
+        let line_start_byte_indexes = source.line_start_byte_indexes();
+        let first_line = source.line_index_of_containing_line(span.start);
+        let last_included_byte = if span.end > span.start {
+            span.end - 1
+        } else {
+            span.start
+        };
+        let last_line = source.line_index_of_containing_line(last_included_byte);
+        for line_index in first_line..=last_line {
+            let line_start = line_start_byte_indexes[line_index];
+            let line_end = line_start_byte_indexes
+                .get(line_index + 1)
+                .copied()
+                .unwrap_or(source.text.len());
+            let line_text =
+                source.text[line_start..line_end].trim_end_matches(|ch| ch == '\n' || ch == '\r');
+            let segment_start = span.start.max(line_start).min(line_end);
+            let segment_end = span.end.min(line_end).max(segment_start);
+            let leading_width = display_width(&source.text[line_start..segment_start]);
+            let caret_width = display_width(&source.text[segment_start..segment_end]).max(1);
+            result.push('\n');
+            result.push_str(line_text);
+            result.push('\n');
+            for _ in 0..leading_width {
+                result.push(' ');
+            }
+            for _ in 0..caret_width {
+                result.push('^');
+            }
+            if line_index == last_line && !label.is_empty() {
+                result.push(' ');
+                result.push_str(label);
+            }
+        }
+    }
+    /// render `self` as a multi-line, rustc-style diagnostic: the `file:line:col: error:
+    /// message` header (the same text [`Display`](fmt::Display) produces), followed by the
+    /// offending source line(s) with a run of `^` carets underlining `self.span`, one caret
+    /// run per line for spans that cross line boundaries, then the same for every span in
+    /// `self.secondary` (each labeled inline after its final caret run), then `self.note` if
+    /// present.
+    ///
+    /// `source` must be the same source code `self` was produced from. Returns just the
+    /// header if `self.span` is `None`.
+    pub fn render_snippet(&self, source: &FromTextSourceCode) -> String {
+        let mut result = self.to_string();
+        let span = match &self.span {
+            Some(span) => span.clone(),
+            None => return result,
+        };
+        Self::render_span(&mut result, source, &span, "");
+        for (span, label) in &self.secondary {
+            Self::render_span(&mut result, source, span, label);
+        }
+        if let Some(suggestion) = &self.suggestion {
+            result.push('\n');
+            result.push_str("help: replace with `");
+            result.push_str(&suggestion.replacement);
+            result.push('`');
+        }
+        if let Some(note) = &self.note {
+            result.push('\n');
+            result.push_str("note: ");
+            result.push_str(note);
+        }
+        result
     }
 }
 
+/// the Levenshtein (edit) distance between `a` and `b`: the minimum number of single-character
+/// insertions, deletions, and substitutions needed to turn `a` into `b`.
+///
+/// used by [`suggest_closest`] to build `help: did you mean \`foo\`?` messages for typoed
+/// keywords, field names, and identifiers.
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // only the previous row is ever needed, so a two-row buffer is enough; `previous_row[j]`
+    // starts as the cost of turning an empty `a` prefix into `b`'s first `j` characters.
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+    let mut current_row = vec![0; b.len() + 1];
+    for (i, a_ch) in a.chars().enumerate() {
+        current_row[0] = i + 1;
+        for (j, &b_ch) in b.iter().enumerate() {
+            let substitution_cost = if a_ch == b_ch { 0 } else { 1 };
+            current_row[j + 1] = (previous_row[j + 1] + 1)
+                .min(current_row[j] + 1)
+                .min(previous_row[j] + substitution_cost);
+        }
+        mem::swap(&mut previous_row, &mut current_row);
+    }
+    previous_row[b.len()]
+}
+
+/// finds the `candidate` closest to the typoed `text`, for use in `help: did you mean
+/// \`foo\`?` suggestions, or `None` if no candidate is close enough to be worth suggesting.
+///
+/// a candidate is only suggested if its edit distance from `text` is within
+/// `max(1, text.chars().count() / 3)`; ties are broken in favor of whichever candidate
+/// `candidates` yields first.
+pub(crate) fn suggest_closest<'a>(
+    text: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let threshold = (text.chars().count() / 3).max(1);
+    let mut best: Option<(&'a str, usize)> = None;
+    for candidate in candidates {
+        let distance = levenshtein_distance(text, candidate);
+        if distance <= threshold {
+            if let Some((_, best_distance)) = best {
+                if distance >= best_distance {
+                    continue;
+                }
+            }
+            best = Some((candidate, distance));
+        }
+    }
+    best.map(|(candidate, _)| candidate)
+}
+
 /// a location in the source code for `FromText`
 #[derive(Copy, Clone, Debug)]
 pub struct TextLocation<'a> {
@@ -251,6 +625,26 @@ impl Iterator for TextLocation<'_> {
     }
 }
 
+/// the byte range a [`TextLocation`] or [`TextSpan`] covers, for [`FromTextState::error_at`]
+/// to stash on the [`FromTextError`] it produces so [`FromTextError::render_snippet`] can
+/// later underline it.
+pub trait ErrorByteRange {
+    /// the byte range `self` covers
+    fn error_byte_range(&self) -> Range<usize>;
+}
+
+impl ErrorByteRange for Range<usize> {
+    fn error_byte_range(&self) -> Range<usize> {
+        self.clone()
+    }
+}
+
+impl ErrorByteRange for TextLocation<'_> {
+    fn error_byte_range(&self) -> Range<usize> {
+        self.byte_index..self.byte_index
+    }
+}
+
 impl<'a> TextLocation<'a> {
     /// create a new `TextLocation` at the specified 0-based byte index.
     ///
@@ -279,6 +673,25 @@ impl<'a> TextLocation<'a> {
     }
 }
 
+const TAB_WIDTH: usize = 4;
+
+/// the number of terminal columns `text` renders as, expanding tabs to the next multiple of
+/// [`TAB_WIDTH`] and using `unicode_width` for everything else.
+///
+/// shared by the `TextLocation -> FromTextErrorLocation` conversion below (to compute a
+/// point's column number) and [`FromTextError::render_snippet`] (to line carets up under
+/// wide/multi-byte characters and tabs), so both agree on what column a byte index renders at.
+fn display_width(text: &str) -> usize {
+    text.chars().fold(0, |col, ch| {
+        // col is zero-based
+        if ch == '\t' {
+            (col + TAB_WIDTH) / TAB_WIDTH * TAB_WIDTH
+        } else {
+            col + ch.width().unwrap_or(0)
+        }
+    })
+}
+
 impl From<TextLocation<'_>> for FromTextErrorLocation {
     /// Convert to `FromTextErrorLocation`.
     /// This is a relatively expensive operation since line and column information needs to be calculated.
@@ -291,17 +704,19 @@ impl From<TextLocation<'_>> for FromTextErrorLocation {
             .line_index_of_containing_line(text_location.byte_index);
         let line_start_index = text_location.source_code.line_start_byte_indexes()[line_index];
         let line_number = line_index + 1;
-        const TAB_WIDTH: usize = 4;
-        let column_number = 1 + text[line_start_index..byte_index]
-            .chars()
-            .fold(0, |col, ch| {
-                // col is zero-based
-                if ch == '\t' {
-                    (col + TAB_WIDTH) / TAB_WIDTH * TAB_WIDTH
-                } else {
-                    col + ch.width().unwrap_or(0)
-                }
-            });
+        // the common case -- an all-ASCII, tab-free line -- needs no `unicode_width` fold at
+        // all: every byte is exactly one column wide, so the column is just the byte count.
+        // `analyze_source_file` already recorded whether that's true for this exact range, so
+        // checking it is O(log non-ASCII byte count) rather than O(line length).
+        let column_number = 1 + if text_location.source_code.has_tabs()
+            || text_location
+                .source_code
+                .has_non_ascii_in_range(line_start_index..byte_index)
+        {
+            display_width(&text[line_start_index..byte_index])
+        } else {
+            byte_index - line_start_index
+        };
         FromTextErrorLocation {
             file_name,
             byte_index,
@@ -331,6 +746,12 @@ impl PartialEq for TextSpan<'_> {
     }
 }
 
+impl ErrorByteRange for TextSpan<'_> {
+    fn error_byte_range(&self) -> Range<usize> {
+        self.byte_indexes()
+    }
+}
+
 impl<'a> TextSpan<'a> {
     /// create a new `TextSpan` starting with `start` and up to but not including `end`.
     ///
@@ -519,6 +940,7 @@ keywords! {
         RF32 = "rf32",
         RI32 = "ri32",
         Size = "size",
+        Splat = "splat",
         Struct = "struct",
         True = "true",
         Undef = "undef",
@@ -539,6 +961,15 @@ keywords! {
     }
 }
 
+keywords! {
+    /// a floating-point suffix
+    pub enum FloatSuffix {
+        F16 = "f16",
+        F32 = "f32",
+        F64 = "f64",
+    }
+}
+
 macro_rules! punctuation {
     (
         $(#[doc = $enum_doc:literal])*
@@ -659,11 +1090,42 @@ impl<'t> From<&'t str> for IdentifierOrKeyword<'t> {
     }
 }
 
+/// which lexical variant of string literal a [`StringToken`] is, mirroring Rust's own
+/// `"..."`/`r"..."`/`b"..."`/`br"..."` family
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum StringTokenKind {
+    /// `"..."`: backslash escape sequences are processed, content is arbitrary Unicode text. A
+    /// backslash immediately followed by a line ending is a line-continuation escape -- it and
+    /// the continued line's leading whitespace are dropped from the value -- so a literal may
+    /// span multiple source lines without embedding that indentation.
+    Plain,
+    /// `r"..."` or `r#"..."#`: no escape processing, content is arbitrary Unicode text
+    Raw,
+    /// `b"..."`: backslash escape sequences are processed, content must be ASCII
+    Byte,
+    /// `br"..."` or `br#"..."#`: no escape processing, content must be ASCII
+    RawByte,
+}
+
+impl StringTokenKind {
+    /// true for [`Byte`](Self::Byte)/[`RawByte`](Self::RawByte), whose content must be ASCII
+    pub fn is_byte(self) -> bool {
+        matches!(self, StringTokenKind::Byte | StringTokenKind::RawByte)
+    }
+    /// true for [`Raw`](Self::Raw)/[`RawByte`](Self::RawByte), which skip backslash-escape processing
+    pub fn is_raw(self) -> bool {
+        matches!(self, StringTokenKind::Raw | StringTokenKind::RawByte)
+    }
+}
+
 /// a string literal token
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct StringToken<'t> {
-    /// the source text for the string literal excluding the enclosing quotes
+    /// the source text for the string literal excluding the enclosing quotes (and, for raw
+    /// variants, the `r`/`b` prefix and `#`s)
     pub source_text: &'t str,
+    /// which of `"..."`/`r"..."`/`b"..."`/`br"..."` `self` was written as
+    pub kind: StringTokenKind,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -741,14 +1203,39 @@ impl StringToken<'_> {
             _ => Err("invalid escape sequence; unicode escapes must be of the form \\u{1234}"),
         }
     }
-    fn parse_char(location: &mut TextLocation) -> Result<char, &'static str> {
+    /// the message [`parse_char`](Self::parse_char) raises for a raw, unescaped line ending --
+    /// given a name so [`FromTextState::parse_escaped_string_body`] can recognize it and attach
+    /// a concrete fix-it suggestion
+    const LINE_ENDING_NOT_ALLOWED_MESSAGE: &'static str =
+        r#"line-ending not allowed in string, use "\n" and/or "\r" instead"#;
+    /// a backslash immediately followed by a line ending: consumes the line ending (and a
+    /// following `\n` if the line ending was `\r`) plus any further whitespace, producing no
+    /// character at all. This lets a plain (non-raw) string literal span multiple source lines
+    /// without embedding the indentation of the continued line, mirroring Rust's own
+    /// string-continuation escape.
+    fn skip_line_continuation(location: &mut TextLocation) {
+        if location.peek() == Some('\r') {
+            location.next();
+        }
+        if location.peek() == Some('\n') {
+            location.next();
+        }
+        while matches!(location.peek(), Some(ch) if ch.is_ascii_whitespace()) {
+            location.next();
+        }
+    }
+    /// parses one logical character of a plain (non-raw) string's body, returning `None` for a
+    /// line-continuation escape, which consumes source text but contributes nothing to the value
+    fn parse_char(location: &mut TextLocation) -> Result<Option<char>, &'static str> {
         match location.next().ok_or("missing character")? {
-            '\\' => Self::parse_escape_sequence(location),
-            '\n' | '\r' => {
-                Err(r#"line-ending not allowed in string, use "\n" and/or "\r" instead"#)
+            '\\' if matches!(location.peek(), Some('\n') | Some('\r')) => {
+                Self::skip_line_continuation(location);
+                Ok(None)
             }
+            '\\' => Self::parse_escape_sequence(location).map(Some),
+            '\n' | '\r' => Err(Self::LINE_ENDING_NOT_ALLOWED_MESSAGE),
             '\0' => Err(r#"null byte not allowed in string, use "\0" instead"#),
-            ch => Ok(ch),
+            ch => Ok(Some(ch)),
         }
     }
     /// get the decoded value of `self`
@@ -757,13 +1244,19 @@ impl StringToken<'_> {
     ///
     /// Panics if `self.source_code` is not valid.
     pub fn value(self) -> String {
+        if self.kind.is_raw() {
+            // raw variants skip escape processing entirely -- `source_text` already is the value
+            return self.source_text.to_string();
+        }
         let mut value = String::with_capacity(self.source_text.len());
         let source_code = FromTextSourceCode::new("", self.source_text);
         let mut location = TextLocation::new(0, &source_code);
         while location.peek().is_some() {
-            value.push(
-                Self::parse_char(&mut location).expect("StringToken should have valid source_text"),
-            );
+            if let Some(ch) =
+                Self::parse_char(&mut location).expect("StringToken should have valid source_text")
+            {
+                value.push(ch);
+            }
         }
         value
     }
@@ -778,6 +1271,121 @@ pub struct IntegerToken {
     pub suffix: Option<IntegerSuffix>,
 }
 
+/// narrows `value` to its nearest binary16 ("half") bit pattern, rounding ties to even.
+///
+/// `shader-compiler-translate-spirv-to-ir` already has a fuller `f64_to_f16_bits` (with a
+/// caller-chosen `RoundingMode`) for lowering the `OpOpenCLStd` half-precision load/store
+/// family, but this crate sits below that translator in the dependency graph and can't reuse
+/// it -- and a float literal's `f16` suffix only ever needs the IEEE-default rounding, so this
+/// is the round-to-nearest-even case of that same algorithm, standalone.
+pub(crate) fn f64_to_f16_bits_round_to_nearest_even(value: f64) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 48) & 0x8000) as u16;
+    if value.is_nan() {
+        let mantissa52 = bits & 0x000f_ffff_ffff_ffff;
+        let payload = ((mantissa52 >> 42) as u16) & 0x03ff;
+        return sign | 0x7c00 | 0x0200 | payload;
+    }
+    if value.is_infinite() {
+        return sign | 0x7c00;
+    }
+    if value == 0.0 {
+        return sign;
+    }
+    let unbiased = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let mantissa52 = bits & 0x000f_ffff_ffff_ffff;
+    let significand = mantissa52 | (1u64 << 52); // 53-bit significand, implicit bit at position 52
+
+    // lowest representable half exponent (subnormal) is -14 - 10 = -24;
+    // `extra_shift` is how much further than the normal 42-bit shift is
+    // needed to place the significand's bits at a subnormal's position.
+    let extra_shift = (-14 - unbiased).max(0) as u32;
+    let total_shift = 42u32 + extra_shift;
+    if total_shift >= 64 {
+        // magnitude far below the smallest subnormal half: flushes to a signed zero
+        return sign;
+    }
+    let shifted = round_to_nearest_even_shift(significand, total_shift);
+
+    let (exp_field, mantissa): (i64, u64) = if extra_shift == 0 {
+        if shifted >= 0x800 {
+            (unbiased + 15 + 1, 0)
+        } else {
+            (unbiased + 15, shifted & 0x3ff)
+        }
+    } else if shifted >= 0x400 {
+        (1, shifted - 0x400)
+    } else {
+        (0, shifted)
+    };
+
+    if exp_field >= 31 {
+        return sign | 0x7c00;
+    }
+    sign | ((exp_field as u16) << 10) | (mantissa as u16)
+}
+
+/// widens a binary16 ("half") bit pattern to the `f64` it represents -- exactly, since every
+/// finite half value (and every half `NaN` payload) fits losslessly in a double.
+///
+/// the inverse of [`f64_to_f16_bits_round_to_nearest_even`], used to print half constants as
+/// decimal text instead of their raw bit pattern.
+pub(crate) fn f16_bits_to_f64(bits: u16) -> f64 {
+    let sign = u64::from(bits & 0x8000) << 48;
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = u64::from(bits & 0x3ff);
+    if exponent == 0 {
+        if mantissa == 0 {
+            return f64::from_bits(sign);
+        }
+        // subnormal half: normalize the mantissa so its leading 1 becomes the implicit bit
+        let mut mantissa = mantissa;
+        let mut unbiased_exponent: i64 = -14;
+        while mantissa & 0x400 == 0 {
+            mantissa <<= 1;
+            unbiased_exponent -= 1;
+        }
+        mantissa &= 0x3ff;
+        let biased_exponent = (unbiased_exponent + 1023) as u64;
+        return f64::from_bits(sign | (biased_exponent << 52) | (mantissa << 42));
+    }
+    if exponent == 0x1f {
+        return f64::from_bits(sign | (0x7ffu64 << 52) | (mantissa << 42));
+    }
+    let biased_exponent = u64::from(exponent) + (1023 - 15);
+    f64::from_bits(sign | (biased_exponent << 52) | (mantissa << 42))
+}
+
+/// right-shifts `value` by `shift` bits, rounding the discarded bits to nearest, ties to even
+fn round_to_nearest_even_shift(value: u64, shift: u32) -> u64 {
+    if shift == 0 {
+        return value;
+    }
+    let truncated = value >> shift;
+    let remainder = value & ((1u64 << shift) - 1);
+    let half = 1u64 << (shift - 1);
+    match remainder.cmp(&half) {
+        core::cmp::Ordering::Greater => truncated + 1,
+        core::cmp::Ordering::Equal if truncated & 1 != 0 => truncated + 1,
+        _ => truncated,
+    }
+}
+
+/// a floating-point literal token
+///
+/// stores the parsed value's IEEE bit pattern -- widened to `u64` regardless of `suffix` --
+/// rather than the value itself, so round-tripping through `ToText`/`FromText` is lossless
+/// even for the bit patterns (signaling `NaN`s, for instance) that don't survive an
+/// arithmetic round trip.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct FloatToken {
+    /// the parsed value's bit pattern: an `f64`'s bits directly if `suffix` is `None` or
+    /// `Some(FloatSuffix::F64)`, otherwise the `f32`/`f16` bit pattern zero-extended to `u64`
+    pub bits: u64,
+    /// the suffix used for the float token
+    pub suffix: Option<FloatSuffix>,
+}
+
 /// the kind of a token
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum TokenKind<'t> {
@@ -789,6 +1397,8 @@ pub enum TokenKind<'t> {
     EndOfFile,
     /// a potentially-suffixed integer
     Integer(IntegerToken),
+    /// a potentially-suffixed floating-point literal
+    Float(FloatToken),
     /// a string literal
     String(StringToken<'t>),
     /// a punctuation character or character sequence
@@ -836,6 +1446,14 @@ impl<'t> TokenKind<'t> {
             None
         }
     }
+    /// return `Some` if `self` is a floating-point literal token
+    pub fn float(self) -> Option<FloatToken> {
+        if let TokenKind::Float(retval) = self {
+            Some(retval)
+        } else {
+            None
+        }
+    }
     /// return `Some` if `self` is a string literal
     pub fn string(self) -> Option<StringToken<'t>> {
         if let TokenKind::String(retval) = self {
@@ -875,6 +1493,36 @@ pub struct Token<'t> {
 /// the character used to start comments
 pub const COMMENT_START_CHAR: char = '#';
 
+/// the character that, immediately following [`COMMENT_START_CHAR`], opens a nested block comment instead of a line comment
+pub const BLOCK_COMMENT_OPEN_CHAR: char = '[';
+
+/// the character that, immediately preceding [`COMMENT_START_CHAR`], closes a nested block comment
+pub const BLOCK_COMMENT_CLOSE_CHAR: char = ']';
+
+/// the comments a parsed entity has attached to it: any number of `leading` comments on their
+/// own line(s) immediately before it (from [`FromTextState::take_leading_comments`]), and at
+/// most one `trailing` comment sharing its last line (from
+/// [`FromTextState::take_trailing_comment_same_line`]), e.g. `block b1 -> [] { } # trailing`.
+///
+/// `ToText` doesn't re-emit this yet -- `Block`/`Loop` have no field to carry it on, and
+/// `ToTextState` (unlike `FromTextState`) never parses source text, so it has nothing to key a
+/// side table on either; this only makes the trivia available to `FromTextState`'s caller via
+/// [`FromTextState::block_comment_trivia`]/[`FromTextState::loop_comment_trivia`].
+#[derive(Clone, Debug, Default)]
+pub struct CommentTrivia<'t> {
+    /// comments on their own line(s) immediately before the entity
+    pub leading: Vec<TextSpan<'t>>,
+    /// a comment sharing the entity's last line, if any
+    pub trailing: Option<TextSpan<'t>>,
+}
+
+impl CommentTrivia<'_> {
+    /// true if there's no comment trivia at all
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_empty() && self.trailing.is_none()
+    }
+}
+
 /// the uninhabited type used for functions that always return `Err` or `Ok`
 ///
 /// Use `Void::into` to convert to `!`, allowing Rust to coerce to any type
@@ -914,16 +1562,22 @@ pub struct FromTextSymbol<'g, T: Id<'g>> {
     pub value: IdRef<'g, T>,
     /// the scope in which `self` is visible
     pub scope: FromTextScopeId,
+    /// the byte range of the name's defining occurrence, for "name first defined here"
+    /// secondary-span diagnostics when a later name collides with it -- see
+    /// [`FromTextSymbolsState::insert_symbol`]
+    pub definition_span: Range<usize>,
 }
 
 impl<'g, T: Id<'g>> Clone for FromTextSymbol<'g, T> {
     fn clone(&self) -> Self {
-        *self
+        Self {
+            value: self.value,
+            scope: self.scope,
+            definition_span: self.definition_span.clone(),
+        }
     }
 }
 
-impl<'g, T: Id<'g>> Copy for FromTextSymbol<'g, T> {}
-
 /// extension trait for `FromTextState`
 pub trait FromTextSymbolsStateBase<'g, 't>: BorrowMut<FromTextState<'g, 't>> {
     /// get the parent scope id of `scope`
@@ -992,31 +1646,43 @@ pub trait FromTextSymbolsState<'g, 't, T: Id<'g>>: FromTextSymbolsStateBase<'g,
     ) -> &mut HashMap<NamedId<'g>, FromTextSymbol<'g, T>>;
     /// get the `FromTextSymbol` corresponding to `name` in the symbol table for type `T`
     fn get_symbol(&self, name: NamedId<'g>) -> Option<FromTextSymbol<'g, T>> {
-        self.get_symbol_table(Private::new()).get(&name).copied()
+        self.get_symbol_table(Private::new()).get(&name).cloned()
     }
     /// insert `name` and `symbol` in the symbol table for type `T`.
-    /// returns `Err` without doing anything else if `name` was already in
-    /// the symbol table for type `T`.
+    /// returns the already-present `FromTextSymbol` without doing anything else if `name` was
+    /// already in the symbol table for type `T` -- callers use its `definition_span` to build a
+    /// "name first defined here" secondary-span diagnostic with [`FromTextState::diagnostic`]
     fn insert_symbol(
         &mut self,
         name: NamedId<'g>,
         symbol: FromTextSymbol<'g, T>,
-    ) -> Result<(), ()> {
-        if let Entry::Vacant(entry) = self.get_symbol_table_mut(Private::new()).entry(name) {
-            entry.insert(symbol);
-            Ok(())
-        } else {
-            Err(())
+    ) -> Result<(), FromTextSymbol<'g, T>> {
+        match self.get_symbol_table_mut(Private::new()).entry(name) {
+            Entry::Vacant(entry) => {
+                entry.insert(symbol);
+                Ok(())
+            }
+            Entry::Occupied(entry) => Err(entry.get().clone()),
         }
     }
 }
 
+/// the size of `FromTextState`'s token lookahead buffer, one more than the largest `n`
+/// `FromTextState::peek_token_nth` accepts -- large enough for every disambiguation this grammar needs (e.g. telling a
+/// variable declaration's `name :` from a call's `name (`) with headroom to spare
+const TOKEN_LOOKAHEAD_CAPACITY: usize = 4;
+
 /// state struct for `FromText`
 pub struct FromTextState<'g, 't> {
     global_state: &'g GlobalState<'g>,
     /// the current `TextLocation`
     pub location: TextLocation<'t>,
-    cached_token: Option<Token<'t>>,
+    /// a small ring buffer of tokens already lexed starting at `self.location`, front first --
+    /// lets `peek_token_nth` look more than one token ahead without losing track of where
+    /// `self.location` really is. Valid only while `self.token_buffer.first()`'s span starts at
+    /// `self.location`; anything that moves `self.location` directly (`recover_to_safe_boundary`,
+    /// for instance) invalidates it, and `fill_token_buffer` refills it from scratch.
+    token_buffer: ArrayVec<[Token<'t>; TOKEN_LOOKAHEAD_CAPACITY]>,
     values: HashMap<NamedId<'g>, FromTextSymbol<'g, Value<'g>>>,
     blocks: HashMap<NamedId<'g>, FromTextSymbol<'g, BlockData<'g>>>,
     loops: HashMap<NamedId<'g>, FromTextSymbol<'g, LoopData<'g>>>,
@@ -1027,6 +1693,36 @@ pub struct FromTextState<'g, 't> {
     /// A scope is visible if it is either `self.scope_stack_top` or
     /// a transitive parent of `self.scope_stack_top`.
     pub scope_stack_top: FromTextScopeId,
+    /// when `Some`, `error_at` records the error here and returns only an empty placeholder
+    /// `Err`, allowing parsing to continue past the first problem. Only set while
+    /// running under `FromText::parse_collect_diagnostics`.
+    recovering_errors: Option<Vec<FromTextError>>,
+    /// the open delimiters `parse_delimited_group` has seen but not yet closed, innermost
+    /// last, each paired with the `Punctuation` that closes it and the span of the open
+    /// delimiter itself -- consulted to build `UnmatchedBrace`-style diagnostics
+    delimiter_stack: Vec<(Punctuation, TextSpan<'t>)>,
+    /// comments collected by `skip_whitespace` since the last
+    /// [`take_leading_comments`](Self::take_leading_comments) call, in source order -- drained
+    /// and attached as leading trivia there rather than being discarded the way comments used
+    /// to be
+    collected_comments: Vec<TextSpan<'t>>,
+    /// comment trivia attached to each parsed block, keyed by the block's `IdRef` -- see
+    /// [`Self::set_block_comment_trivia`]
+    block_comment_trivia: HashMap<IdRef<'g, BlockData<'g>>, CommentTrivia<'t>>,
+    /// comment trivia attached to each parsed loop, keyed by the loop's `IdRef` -- see
+    /// [`Self::set_loop_comment_trivia`]
+    loop_comment_trivia: HashMap<IdRef<'g, LoopData<'g>>, CommentTrivia<'t>>,
+}
+
+/// the kind of token boundary `recover_to_safe_boundary` should synchronize to
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RecoveryBoundary {
+    /// synchronize to the next `Punctuation::Semicolon` (consumed) or the closing
+    /// `Punctuation::RCurlyBrace` (not consumed), as used inside a block body
+    StatementEnd,
+    /// synchronize to the next `Punctuation::LCurlyBrace` (not consumed), as used
+    /// while parsing a loop/block header
+    OpeningCurlyBrace,
 }
 
 impl<'g, 't> FromTextSymbolsState<'g, 't, Value<'g>> for FromTextState<'g, 't> {
@@ -1091,7 +1787,7 @@ impl<'g, 't> FromTextState<'g, 't> {
         Self {
             global_state,
             location: TextLocation::new(0, source_code),
-            cached_token: None,
+            token_buffer: ArrayVec::new(),
             values: HashMap::new(),
             blocks: HashMap::new(),
             loops: HashMap::new(),
@@ -1099,23 +1795,251 @@ impl<'g, 't> FromTextState<'g, 't> {
             structs: HashMap::new(),
             parent_scopes: vec![FromTextScopeId::ROOT],
             scope_stack_top: FromTextScopeId::ROOT,
+            recovering_errors: None,
+            delimiter_stack: Vec::new(),
+            collected_comments: Vec::new(),
+            block_comment_trivia: HashMap::new(),
+            loop_comment_trivia: HashMap::new(),
         }
     }
     /// get the `GlobalState` reference
     pub fn global_state(&self) -> &'g GlobalState<'g> {
         self.global_state
     }
-    /// create an error at the specified location with the specified message
-    pub fn error_at<L: Into<FromTextErrorLocation>>(
+    /// the comment trivia attached to the block `block`, if `block` was parsed with any leading
+    /// or trailing comments -- populated by `Block::from_text_with_callbacks`
+    pub fn block_comment_trivia(&self, block: IdRef<'g, BlockData<'g>>) -> Option<&CommentTrivia<'t>> {
+        self.block_comment_trivia.get(&block)
+    }
+    /// the comment trivia attached to the loop `loop_`, if `loop_` was parsed with any leading
+    /// or trailing comments -- populated by `Loop::from_text`
+    pub fn loop_comment_trivia(&self, loop_: IdRef<'g, LoopData<'g>>) -> Option<&CommentTrivia<'t>> {
+        self.loop_comment_trivia.get(&loop_)
+    }
+    /// record `trivia` as the comment trivia for the block `block`, for `Block::from_text` to
+    /// call once it has both `block`'s `IdRef` and the trivia captured around it
+    pub(crate) fn set_block_comment_trivia(
+        &mut self,
+        block: IdRef<'g, BlockData<'g>>,
+        trivia: CommentTrivia<'t>,
+    ) {
+        if !trivia.is_empty() {
+            self.block_comment_trivia.insert(block, trivia);
+        }
+    }
+    /// the loop counterpart to [`set_block_comment_trivia`](Self::set_block_comment_trivia)
+    pub(crate) fn set_loop_comment_trivia(
+        &mut self,
+        loop_: IdRef<'g, LoopData<'g>>,
+        trivia: CommentTrivia<'t>,
+    ) {
+        if !trivia.is_empty() {
+            self.loop_comment_trivia.insert(loop_, trivia);
+        }
+    }
+    /// drain every comment collected by `skip_whitespace` since the last call to this method (or
+    /// since the start of the file) as leading trivia for whatever `FromText` is about to parse
+    /// next. Call this before peeking/parsing the entity's first token -- any later call instead
+    /// picks up comments belonging to whatever comes after it.
+    pub fn take_leading_comments(&mut self) -> Vec<TextSpan<'t>> {
+        mem::take(&mut self.collected_comments)
+    }
+    /// if a comment starts on the same source line as `self.location`, with only non-newline
+    /// whitespace in between, consume and return it as trailing trivia for whatever was just
+    /// parsed; otherwise leave `self.location` untouched and return `None`.
+    ///
+    /// must be called right after finishing an entity, before anything else (a `peek_token`,
+    /// chiefly) skips past the comment and folds it into the next entity's leading comments
+    /// instead via [`take_leading_comments`](Self::take_leading_comments).
+    pub fn take_trailing_comment_same_line(&mut self) -> Option<TextSpan<'t>> {
+        let mut probe = self.location;
+        loop {
+            match probe.peek() {
+                Some(COMMENT_START_CHAR) => break,
+                Some(ch) if ch.is_ascii_whitespace() && ch != '\n' => {
+                    probe.next();
+                }
+                _ => return None,
+            }
+        }
+        self.location = probe;
+        let comment_start = self.location;
+        self.parse_comment().ok()?;
+        Some(TextSpan::new(comment_start, self.location))
+    }
+    /// true if `self` is running in error-recovery mode (see `FromText::parse_collect_errors`)
+    pub fn is_recovering_errors(&self) -> bool {
+        self.recovering_errors.is_some()
+    }
+    /// create an error at the specified location with the specified message.
+    ///
+    /// In error-recovery mode (see `FromText::parse_collect_errors`), this records the
+    /// error and returns an empty placeholder `Err` (with `span: None`) instead of the
+    /// error itself, so callers still propagate with `?` as usual --
+    /// recovery only changes what happens to the error, not the control flow here.
+    pub fn error_at<L: Into<FromTextErrorLocation> + ErrorByteRange + Copy>(
+        &mut self,
+        location: L,
+        message: impl ToString,
+    ) -> Result<Void, FromTextError> {
+        self.error_at_impl(location, message, None)
+    }
+    /// like [`error_at`](Self::error_at), but attaches a fix-it `suggestion` that a caller --
+    /// an editor, or a `rustfix`-style tool -- could use to resolve the diagnostic
+    pub fn error_at_with_suggestion<L: Into<FromTextErrorLocation> + ErrorByteRange + Copy>(
+        &mut self,
+        location: L,
+        message: impl ToString,
+        suggestion: Suggestion,
+    ) -> Result<Void, FromTextError> {
+        self.error_at_impl(location, message, Some(suggestion))
+    }
+    fn error_at_impl<L: Into<FromTextErrorLocation> + ErrorByteRange + Copy>(
         &mut self,
         location: L,
         message: impl ToString,
+        suggestion: Option<Suggestion>,
     ) -> Result<Void, FromTextError> {
-        Err(FromTextError {
+        self.error_at_diagnostic(Diagnostic {
             location: location.into(),
+            span: location.error_byte_range(),
             message: message.to_string(),
+            suggestion,
+            secondary: Vec::new(),
+            note: None,
         })
     }
+    /// start building a multi-span diagnostic at `location` with the given primary `message`;
+    /// chain [`Diagnostic::with_secondary`] and [`Diagnostic::with_note`] on the result, then
+    /// hand it to [`error_at_diagnostic`](Self::error_at_diagnostic). Use this instead of
+    /// [`error_at`](Self::error_at) whenever the diagnostic should also point at another,
+    /// related span -- e.g. "name first defined here" for a duplicate-name error.
+    pub fn diagnostic<L: Into<FromTextErrorLocation> + ErrorByteRange + Copy>(
+        &self,
+        location: L,
+        message: impl ToString,
+    ) -> Diagnostic {
+        Diagnostic {
+            location: location.into(),
+            span: location.error_byte_range(),
+            message: message.to_string(),
+            suggestion: None,
+            secondary: Vec::new(),
+            note: None,
+        }
+    }
+    /// record `diagnostic`, the multi-span counterpart to [`error_at`](Self::error_at): in
+    /// error-recovery mode (see [`FromText::parse_collect_errors`]), this records the
+    /// diagnostic and returns an empty placeholder `Err` instead of the diagnostic itself --
+    /// recovery only changes what happens to the error, not the control flow here.
+    pub fn error_at_diagnostic(&mut self, diagnostic: Diagnostic) -> Result<Void, FromTextError> {
+        let Diagnostic {
+            location,
+            span,
+            message,
+            suggestion,
+            secondary,
+            note,
+        } = diagnostic;
+        let error = FromTextError {
+            span: Some(span),
+            location,
+            message,
+            severity: Severity::Error,
+            suggestion,
+            secondary,
+            note,
+        };
+        if let Some(recovering_errors) = &mut self.recovering_errors {
+            recovering_errors.push(error);
+            Err(FromTextError {
+                location: self.location.into(),
+                message: String::new(),
+                span: None,
+                severity: Severity::Error,
+                suggestion: None,
+                secondary: Vec::new(),
+                note: None,
+            })
+        } else {
+            Err(error)
+        }
+    }
+    /// record a non-fatal `Warning`-severity diagnostic at `location`; unlike
+    /// [`error_at`](Self::error_at), this never aborts parsing -- while running under
+    /// [`FromText::parse_collect_errors`] the warning is added to the collected diagnostics,
+    /// otherwise there is no diagnostic sink to record it into and it is silently dropped
+    pub fn warn_at<L: Into<FromTextErrorLocation> + ErrorByteRange + Copy>(
+        &mut self,
+        location: L,
+        message: impl ToString,
+        suggestion: Option<Suggestion>,
+    ) {
+        if let Some(recovering_errors) = &mut self.recovering_errors {
+            recovering_errors.push(FromTextError {
+                span: Some(location.error_byte_range()),
+                location: location.into(),
+                message: message.to_string(),
+                severity: Severity::Warning,
+                suggestion,
+                secondary: Vec::new(),
+                note: None,
+            });
+        }
+    }
+    /// skip tokens until `self.location` is synchronized to `boundary`, providing a
+    /// safe point to resume parsing after a diagnostic has been recorded.
+    /// For `StatementEnd`, the terminating semicolon is consumed; for
+    /// `OpeningCurlyBrace`, the opening curly brace is left unconsumed so the
+    /// caller's own `parse_parenthesized` call can still see it.
+    pub fn recover_to_safe_boundary(&mut self, boundary: RecoveryBoundary) {
+        loop {
+            let token = match self.peek_token() {
+                Ok(token) => token,
+                Err(_) => return,
+            };
+            match (boundary, token.kind) {
+                (_, TokenKind::EndOfFile) => return,
+                (RecoveryBoundary::StatementEnd, TokenKind::Punct(Punctuation::Semicolon)) => {
+                    let _ = self.parse_token();
+                    return;
+                }
+                (RecoveryBoundary::StatementEnd, TokenKind::Punct(Punctuation::RCurlyBrace)) => {
+                    return;
+                }
+                (RecoveryBoundary::OpeningCurlyBrace, TokenKind::Punct(Punctuation::LCurlyBrace)) => {
+                    return;
+                }
+                _ => {
+                    let _ = self.parse_token();
+                }
+            }
+        }
+    }
+    /// skip tokens until `self.location` is synchronized to either `item_terminator`
+    /// (consumed) or `closing_punct` (not consumed, so the caller's own `parse_parenthesized`
+    /// call still sees it) -- the list-specific counterpart to `recover_to_safe_boundary`,
+    /// used by `ListForm::parse_list_with_extra_callbacks` to resynchronize after a malformed
+    /// list item without losing track of the list's own closing delimiter
+    fn recover_to_list_boundary(&mut self, item_terminator: Punctuation, closing_punct: Punctuation) {
+        loop {
+            let token = match self.peek_token() {
+                Ok(token) => token,
+                Err(_) => return,
+            };
+            match token.kind {
+                TokenKind::EndOfFile => return,
+                TokenKind::Punct(punct) if punct == closing_punct => return,
+                TokenKind::Punct(punct) if punct == item_terminator => {
+                    let _ = self.parse_token();
+                    return;
+                }
+                _ => {
+                    let _ = self.parse_token();
+                }
+            }
+        }
+    }
     fn peek_char(&self) -> Option<char> {
         self.location.peek()
     }
@@ -1134,6 +2058,12 @@ impl<'g, 't> FromTextState<'g, 't> {
         if self.peek_char() != Some(COMMENT_START_CHAR) {
             self.error_at_peek_char("missing comment")?;
         }
+        let start_location = self.location;
+        self.next_char();
+        if self.peek_char() == Some(BLOCK_COMMENT_OPEN_CHAR) {
+            self.next_char();
+            return self.parse_block_comment_body(start_location);
+        }
         loop {
             match self.next_char() {
                 None | Some('\n') => break,
@@ -1142,10 +2072,47 @@ impl<'g, 't> FromTextState<'g, 't> {
         }
         Ok(())
     }
+    /// scans the body of a nested block comment opened by `#[` -- the `outermost_open`
+    /// location of that opening `#[` -- maintaining a depth counter that's incremented
+    /// on every nested `#[` and decremented on every `]#`, only returning to normal
+    /// lexing once depth reaches zero. This lets a block comment contain `#` line
+    /// comments, or other block comments, without ending early.
+    fn parse_block_comment_body(
+        &mut self,
+        outermost_open: TextLocation<'t>,
+    ) -> Result<(), FromTextError> {
+        let mut depth: u32 = 1;
+        loop {
+            match self.next_char() {
+                None => {
+                    let span = TextSpan::new(outermost_open, self.location);
+                    self.error_at(span, "unterminated block comment")?;
+                    return Ok(());
+                }
+                Some(BLOCK_COMMENT_CLOSE_CHAR) if self.peek_char() == Some(COMMENT_START_CHAR) => {
+                    self.next_char();
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                Some(COMMENT_START_CHAR) if self.peek_char() == Some(BLOCK_COMMENT_OPEN_CHAR) => {
+                    self.next_char();
+                    depth += 1;
+                }
+                _ => {}
+            }
+        }
+    }
     fn skip_whitespace(&mut self) -> Result<(), FromTextError> {
         loop {
             match self.peek_char() {
-                Some(COMMENT_START_CHAR) => self.parse_comment()?,
+                Some(COMMENT_START_CHAR) => {
+                    let comment_start = self.location;
+                    self.parse_comment()?;
+                    self.collected_comments
+                        .push(TextSpan::new(comment_start, self.location));
+                }
                 Some(ch) => {
                     if !ch.is_ascii_whitespace() {
                         break;
@@ -1181,10 +2148,101 @@ impl<'g, 't> FromTextState<'g, 't> {
         let span = TextSpan::new(start_location, self.location);
         match span.text().parse::<IntegerSuffix>() {
             Ok(retval) => Ok(Some(retval)),
-            Err(_) => self.error_at(span, "invalid integer suffix")?.into(),
+            Err(_) => match suggest_closest(
+                span.text(),
+                IntegerSuffix::VALUES.iter().copied().map(IntegerSuffix::text),
+            ) {
+                Some(suggestion) => self
+                    .error_at_with_suggestion(
+                        span,
+                        "invalid integer suffix",
+                        Suggestion {
+                            span: span.byte_indexes(),
+                            replacement: suggestion.to_string(),
+                            applicability: Applicability::MaybeIncorrect,
+                        },
+                    )?
+                    .into(),
+                None => self.error_at(span, "invalid integer suffix")?.into(),
+            },
+        }
+    }
+    fn parse_optional_float_suffix(&mut self) -> Result<Option<FloatSuffix>, FromTextError> {
+        let start_location = self.location;
+        if self.peek_char().map(char::is_identifier_start) != Some(true) {
+            return Ok(None);
+        }
+        while self.peek_char().map(char::is_identifier_continue) == Some(true) {
+            self.next_char();
+        }
+        let span = TextSpan::new(start_location, self.location);
+        match span.text().parse::<FloatSuffix>() {
+            Ok(retval) => Ok(Some(retval)),
+            Err(_) => self.error_at(span, "invalid float suffix")?.into(),
+        }
+    }
+    /// having already scanned a leading run of decimal digits, consumes a `.`-fraction and/or
+    /// `e`/`E`-exponent if one follows, then the optional float suffix, returning `None` (and
+    /// consuming nothing further) if neither a fraction nor an exponent is present -- in which
+    /// case the digits scanned so far are an [`IntegerToken`] after all.
+    ///
+    /// a lone trailing `.` with no fractional digit after it (e.g. a field-access-like
+    /// `foo.bar`, or a struct literal's `.`) is deliberately left unconsumed rather than treated
+    /// as the start of a fraction, matching rustc's own integer-vs-float split.
+    fn try_parse_float_continuation(
+        &mut self,
+        digits_start_location: TextLocation<'t>,
+    ) -> Result<Option<FloatToken>, FromTextError> {
+        let mut probe = self.location;
+        let mut is_float = false;
+        if probe.peek() == Some('.') {
+            let mut after_dot = probe;
+            after_dot.next();
+            if after_dot.peek().map(|ch| ch.is_ascii_digit()) == Some(true) {
+                probe = after_dot;
+                while probe.peek().map(|ch| ch.is_ascii_digit()) == Some(true) {
+                    probe.next();
+                }
+                is_float = true;
+            }
+        }
+        if let Some('e') | Some('E') = probe.peek() {
+            let mut after_e = probe;
+            after_e.next();
+            if let Some('+') | Some('-') = after_e.peek() {
+                after_e.next();
+            }
+            if after_e.peek().map(|ch| ch.is_ascii_digit()) == Some(true) {
+                while after_e.peek().map(|ch| ch.is_ascii_digit()) == Some(true) {
+                    after_e.next();
+                }
+                probe = after_e;
+                is_float = true;
+            }
+        }
+        if !is_float {
+            return Ok(None);
         }
+        self.location = probe;
+        let text = TextSpan::new(digits_start_location, self.location).text();
+        let suffix = self.parse_optional_float_suffix()?;
+        let bits = match suffix {
+            Some(FloatSuffix::F32) => match text.parse::<f32>() {
+                Ok(value) => u64::from(value.to_bits()),
+                Err(_) => return self.error_at(digits_start_location, "invalid float literal")?.into(),
+            },
+            Some(FloatSuffix::F16) => match text.parse::<f64>() {
+                Ok(value) => u64::from(f64_to_f16_bits_round_to_nearest_even(value)),
+                Err(_) => return self.error_at(digits_start_location, "invalid float literal")?.into(),
+            },
+            None | Some(FloatSuffix::F64) => match text.parse::<f64>() {
+                Ok(value) => value.to_bits(),
+                Err(_) => return self.error_at(digits_start_location, "invalid float literal")?.into(),
+            },
+        };
+        Ok(Some(FloatToken { bits, suffix }))
     }
-    fn parse_integer(&mut self) -> Result<IntegerToken, FromTextError> {
+    fn parse_number(&mut self) -> Result<TokenKind<'t>, FromTextError> {
         if self.peek_char().map(|ch| ch.is_ascii_digit()) != Some(true) {
             self.error_at_peek_char("expected number")?;
         }
@@ -1209,14 +2267,17 @@ impl<'g, 't> FromTextState<'g, 't> {
                     radix = 2;
                 }
                 Some(ch) if ch.is_ascii_digit() => self
-                    .error_at_peek_char("octal numbers must start with 0o or 0O")?
+                    .error_at_with_suggestion(
+                        self.location,
+                        "octal numbers must start with 0o or 0O",
+                        Suggestion {
+                            span: self.location.error_byte_range(),
+                            replacement: "o".to_string(),
+                            applicability: Applicability::MachineApplicable,
+                        },
+                    )?
                     .into(),
-                _ => {
-                    return Ok(IntegerToken {
-                        value: 0,
-                        suffix: self.parse_optional_integer_suffix()?,
-                    })
-                }
+                _ => radix = 10,
             }
         } else {
             radix = 10;
@@ -1229,25 +2290,33 @@ impl<'g, 't> FromTextState<'g, 't> {
         {
             self.next_char();
         }
+        // `.`/`e` can only start a fraction/exponent after a plain decimal digit run --
+        // a `0x`/`0o`/`0b`-prefixed literal always takes the integer path
+        if radix == 10 {
+            if let Some(float_token) = self.try_parse_float_continuation(digits_start_location)? {
+                return Ok(TokenKind::Float(float_token));
+            }
+        }
         let digits = TextSpan::new(digits_start_location, self.location).text();
         let suffix = self.parse_optional_integer_suffix()?;
         match u64::from_str_radix(digits, radix) {
-            Ok(value) => Ok(IntegerToken { value, suffix }),
+            Ok(value) => Ok(TokenKind::Integer(IntegerToken { value, suffix })),
             _ => self
                 .error_at(digits_start_location, "number too big")?
                 .into(),
         }
     }
-    fn parse_string(&mut self) -> Result<StringToken<'t>, FromTextError> {
-        if self.peek_char() != Some(StringToken::QUOTE) {
-            self.error_at_peek_char("missing string")?;
-        }
-        let quote_location = self.location;
-        self.next_char();
+    /// parses an unprefixed `"..."` string, or the escaped body of a `b"..."` byte string
+    /// once `kind` and the opening quote have already been determined
+    fn parse_escaped_string_body(
+        &mut self,
+        error_location: TextLocation<'t>,
+        kind: StringTokenKind,
+    ) -> Result<StringToken<'t>, FromTextError> {
         let string_body_start_location = self.location;
         loop {
             match self.peek_char() {
-                None => self.error_at(quote_location, "unterminated string")?.into(),
+                None => self.error_at(error_location, "unterminated string")?.into(),
                 Some(StringToken::QUOTE) => {
                     let string_body_end_location = self.location;
                     self.next_char();
@@ -1257,15 +2326,150 @@ impl<'g, 't> FromTextState<'g, 't> {
                             string_body_end_location,
                         )
                         .text(),
+                        kind,
                     });
                 }
-                _ => match StringToken::parse_char(&mut self.location) {
-                    Ok(_) => {}
-                    Err(message) => self.error_at_peek_char(message)?.into(),
-                },
+                _ => {
+                    let char_start_location = self.location;
+                    match StringToken::parse_char(&mut self.location) {
+                        Ok(Some(ch)) if kind.is_byte() && !ch.is_ascii() => {
+                            self.error_at(
+                                TextSpan::new(char_start_location, self.location),
+                                "byte string literal must be ASCII",
+                            )?;
+                        }
+                        Ok(_) => {}
+                        Err(message) if message == StringToken::LINE_ENDING_NOT_ALLOWED_MESSAGE => {
+                            let char_span = TextSpan::new(char_start_location, self.location);
+                            let replacement = match char_span.text() {
+                                "\r" => "\\r",
+                                _ => "\\n",
+                            };
+                            self.error_at_with_suggestion(
+                                char_span,
+                                message,
+                                Suggestion {
+                                    span: char_span.byte_indexes(),
+                                    replacement: replacement.to_string(),
+                                    applicability: Applicability::MachineApplicable,
+                                },
+                            )?
+                            .into()
+                        }
+                        Err(message) => self.error_at_peek_char(message)?.into(),
+                    }
+                }
+            }
+        }
+    }
+    /// parses the raw body of a `r"..."`/`r#"..."#`/`br"..."`/`br#"..."#` string, once `kind`,
+    /// `hash_count`, and the opening quote have already been determined. No escape processing
+    /// is done; the closing delimiter is the first `"` followed by at least `hash_count` `#`s.
+    fn parse_raw_string_body(
+        &mut self,
+        error_location: TextLocation<'t>,
+        kind: StringTokenKind,
+        hash_count: usize,
+    ) -> Result<StringToken<'t>, FromTextError> {
+        let string_body_start_location = self.location;
+        loop {
+            match self.peek_char() {
+                None => self
+                    .error_at(
+                        error_location,
+                        format!(
+                            "unterminated raw string literal: missing closing '\"' followed by {} '#'{}",
+                            hash_count,
+                            if hash_count == 1 { "" } else { "s" }
+                        ),
+                    )?
+                    .into(),
+                Some(StringToken::QUOTE) => {
+                    let string_body_end_location = self.location;
+                    let mut probe = self.location;
+                    probe.next();
+                    let mut matched_hashes = 0;
+                    while matched_hashes < hash_count && probe.peek() == Some('#') {
+                        probe.next();
+                        matched_hashes += 1;
+                    }
+                    if matched_hashes == hash_count {
+                        self.location = probe;
+                        return Ok(StringToken {
+                            source_text: TextSpan::new(
+                                string_body_start_location,
+                                string_body_end_location,
+                            )
+                            .text(),
+                            kind,
+                        });
+                    }
+                    // not enough matching '#'s to close the literal -- this quote is just content
+                    self.next_char();
+                }
+                _ => {
+                    let char_start_location = self.location;
+                    let ch = self.next_char().expect("peek_char returned Some");
+                    if kind.is_byte() && !ch.is_ascii() {
+                        self.error_at(
+                            TextSpan::new(char_start_location, self.location),
+                            "byte string literal must be ASCII",
+                        )?;
+                    }
+                }
             }
         }
     }
+    /// if positioned at a `"` (optionally a byte-string/raw-string prefix first), parses the
+    /// whole string literal token (escaped or raw, plain or byte); otherwise leaves `self`
+    /// untouched and returns `None` so the caller can fall back to identifier/keyword parsing
+    fn try_parse_string(&mut self) -> Result<Option<StringToken<'t>>, FromTextError> {
+        let start_location = self.location;
+        let mut probe = self.location;
+        let is_byte = probe.peek() == Some('b');
+        if is_byte {
+            probe.next();
+        }
+        let is_raw = probe.peek() == Some('r');
+        if is_raw {
+            probe.next();
+        }
+        if !is_byte && !is_raw {
+            if probe.peek() != Some(StringToken::QUOTE) {
+                return Ok(None);
+            }
+        } else if probe.peek() != Some(StringToken::QUOTE)
+            && !(is_raw && probe.peek() == Some('#'))
+        {
+            // `b`/`r` not immediately followed by a quote or (for raw strings) a run of '#'s --
+            // this is an ordinary identifier like `bar`, not a string prefix.
+            return Ok(None);
+        }
+        let kind = match (is_byte, is_raw) {
+            (false, false) => StringTokenKind::Plain,
+            (true, false) => StringTokenKind::Byte,
+            (false, true) => StringTokenKind::Raw,
+            (true, true) => StringTokenKind::RawByte,
+        };
+        if is_raw {
+            let mut hash_count = 0;
+            while probe.peek() == Some('#') {
+                probe.next();
+                hash_count += 1;
+            }
+            if probe.peek() != Some(StringToken::QUOTE) {
+                return Ok(None);
+            }
+            probe.next();
+            self.location = probe;
+            return self
+                .parse_raw_string_body(start_location, kind, hash_count)
+                .map(Some);
+        }
+        probe.next();
+        self.location = probe;
+        self.parse_escaped_string_body(start_location, kind).map(Some)
+    }
     fn parse_punct(&mut self) -> Result<Punctuation, FromTextError> {
         if self.peek_char().is_none() {
             self.error_at_peek_char("missing punctuation")?;
@@ -1303,16 +2507,24 @@ impl<'g, 't> FromTextState<'g, 't> {
                 kind: TokenKind::EndOfFile,
                 span: TextSpan::new(start_location, self.location),
             }),
-            Some(StringToken::QUOTE) => Ok(Token {
-                kind: TokenKind::String(self.parse_string()?),
-                span: TextSpan::new(start_location, self.location),
-            }),
+            Some(ch) if ch == StringToken::QUOTE || ch == 'b' || ch == 'r' => {
+                match self.try_parse_string()? {
+                    Some(string_token) => Ok(Token {
+                        kind: TokenKind::String(string_token),
+                        span: TextSpan::new(start_location, self.location),
+                    }),
+                    None => Ok(Token {
+                        kind: self.parse_identifier_or_keyword()?.into(),
+                        span: TextSpan::new(start_location, self.location),
+                    }),
+                }
+            }
             Some(ch) if ch.is_identifier_start() => Ok(Token {
                 kind: self.parse_identifier_or_keyword()?.into(),
                 span: TextSpan::new(start_location, self.location),
             }),
             Some(ch) if ch.is_ascii_digit() => Ok(Token {
-                kind: TokenKind::Integer(self.parse_integer()?),
+                kind: self.parse_number()?,
                 span: TextSpan::new(start_location, self.location),
             }),
             _ => Ok(Token {
@@ -1321,30 +2533,61 @@ impl<'g, 't> FromTextState<'g, 't> {
             }),
         }
     }
+    /// make sure `self.token_buffer` holds at least `n + 1` tokens, each one starting right
+    /// where the previous one ends (skipping comments/whitespace in between, exactly as
+    /// `parse_token_impl` would), without disturbing `self.location`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n >= TOKEN_LOOKAHEAD_CAPACITY`.
+    fn fill_token_buffer(&mut self, n: usize) -> Result<(), FromTextError> {
+        assert!(
+            n < TOKEN_LOOKAHEAD_CAPACITY,
+            "peek_token_nth({}) exceeds the {}-token lookahead buffer",
+            n,
+            TOKEN_LOOKAHEAD_CAPACITY
+        );
+        if self.token_buffer.first().map(|token| token.span.start()) != Some(self.location) {
+            // the buffer no longer starts where we are -- something moved `self.location`
+            // directly (e.g. `recover_to_safe_boundary`) since it was last filled
+            self.token_buffer.clear();
+        }
+        let committed_location = self.location;
+        if let Some(last) = self.token_buffer.last() {
+            self.location = last.span.end();
+        }
+        let result = (|| {
+            while self.token_buffer.len() <= n {
+                let token = self.parse_token_impl()?;
+                self.token_buffer.push(token);
+            }
+            Ok(())
+        })();
+        self.location = committed_location;
+        result
+    }
+    /// return the token `n` tokens ahead of `self.location` (`n == 0` is the same token
+    /// `peek_token` returns), without advancing `self.location` past any of them.
+    pub fn peek_token_nth(&mut self, n: usize) -> Result<Token<'t>, FromTextError> {
+        self.fill_token_buffer(n)?;
+        Ok(self.token_buffer[n])
+    }
     /// return the next token, but resetting `self.location` to the beginning
     /// of the next token so that it is returned again at the next
     /// `peek_token` or `parse_token` call.
     /// However, this does advance `self.location` past any intervening comments or whitespace.
     pub fn peek_token(&mut self) -> Result<Token<'t>, FromTextError> {
-        if let Some(cached_token) = self.cached_token {
-            if cached_token.span.start() == self.location {
-                return Ok(cached_token);
-            }
-        }
-        let token = self.parse_token_impl()?;
-        self.location = token.span.start();
-        self.cached_token = Some(token);
-        Ok(token)
+        self.peek_token_nth(0)
     }
     /// parse the next token, advancing `self.location` to right after it.
     pub fn parse_token(&mut self) -> Result<Token<'t>, FromTextError> {
-        if let Some(cached_token) = self.cached_token.take() {
-            if cached_token.span.start() == self.location {
-                self.location = cached_token.span.end();
-                return Ok(cached_token);
-            }
+        let token = self.peek_token_nth(0)?;
+        for i in 1..self.token_buffer.len() {
+            self.token_buffer[i - 1] = self.token_buffer[i];
         }
-        self.parse_token_impl()
+        self.token_buffer.pop();
+        self.location = token.span.end();
+        Ok(token)
     }
     /// parse the next token, erroring if it is not the passed-in `punct`
     pub fn parse_punct_token_or_error(
@@ -1373,6 +2616,10 @@ impl<'g, 't> FromTextState<'g, 't> {
     /// parse `open_paren` then call `body` then parse `close_paren`.
     /// Useful for parsing source that is grouped using delimiter
     /// punctuation, such as `"(i8)"`.
+    ///
+    /// built atop [`parse_delimited_group`](Self::parse_delimited_group), so a stray or
+    /// mismatched delimiter anywhere inside `body` -- not just right here -- is still reported
+    /// precisely rather than surfacing later as a confusing "extra tokens at end".
     pub fn parse_parenthesized<T, F: FnOnce(&mut Self) -> Result<T, FromTextError>>(
         &mut self,
         open_paren: Punctuation,
@@ -1381,10 +2628,95 @@ impl<'g, 't> FromTextState<'g, 't> {
         missing_close_paren_error_msg: impl ToString,
         body: F,
     ) -> Result<T, FromTextError> {
-        self.parse_punct_token_or_error(open_paren, missing_open_paren_error_msg)?;
-        let retval = body(self)?;
-        self.parse_punct_token_or_error(close_paren, missing_close_paren_error_msg)?;
-        Ok(retval)
+        self.parse_delimited_group(
+            open_paren,
+            missing_open_paren_error_msg,
+            close_paren,
+            missing_close_paren_error_msg,
+            body,
+        )
+        .map(|(retval, _span)| retval)
+    }
+    /// like [`parse_parenthesized`](Self::parse_parenthesized), but also returns the `TextSpan`
+    /// of the whole `open ... close` group, and tracks `open`/`close` on `self`'s stack of open
+    /// delimiters while `body` runs.
+    ///
+    /// if the token where `close` was expected is some other closing delimiter instead, and
+    /// that delimiter matches a still-open entry further down the stack, the diagnostic points
+    /// at that entry's open span as the best guess for what's actually unclosed -- the same
+    /// `UnmatchedBrace` diagnostic rustc's lexer reports for a stray/mismatched brace -- rather
+    /// than just complaining about the unexpected token in front of us.
+    pub fn parse_delimited_group<T, F: FnOnce(&mut Self) -> Result<T, FromTextError>>(
+        &mut self,
+        open: Punctuation,
+        missing_open_error_msg: impl ToString,
+        close: Punctuation,
+        missing_close_error_msg: impl ToString,
+        body: F,
+    ) -> Result<(T, TextSpan<'t>), FromTextError> {
+        let open_token = self.parse_punct_token_or_error(open, missing_open_error_msg)?;
+        self.delimiter_stack.push((close, open_token.span));
+        let retval = match body(self) {
+            Ok(retval) => retval,
+            Err(error) => {
+                self.delimiter_stack.pop();
+                return Err(error);
+            }
+        };
+        let close_token = self.parse_token()?;
+        self.delimiter_stack.pop();
+        if close_token.kind.punct() == Some(close) {
+            return Ok((
+                retval,
+                TextSpan::new(open_token.span.start(), close_token.span.end()),
+            ));
+        }
+        self.unmatched_delimiter_error(
+            open_token.span,
+            close_token,
+            missing_close_error_msg,
+        )?
+        .into()
+    }
+    /// builds the diagnostic for [`parse_delimited_group`](Self::parse_delimited_group) finding
+    /// `close_token` where the delimiter opened at `open_span` should have been closed.
+    fn unmatched_delimiter_error(
+        &mut self,
+        open_span: TextSpan<'t>,
+        close_token: Token<'t>,
+        missing_close_error_msg: impl ToString,
+    ) -> Result<Void, FromTextError> {
+        if close_token.kind.is_end_of_file() {
+            // every delimiter still open when we ran off the end of the source is
+            // unclosed -- report the innermost (most recently pushed) first
+            while let Some((expected_close, unclosed_open_span)) = self.delimiter_stack.pop() {
+                self.error_at(
+                    unclosed_open_span,
+                    format!("unclosed delimiter, expected a matching `{}`", expected_close.text()),
+                )?;
+            }
+            return self.error_at(open_span, missing_close_error_msg);
+        }
+        if let Some(found_close) = close_token.kind.punct() {
+            if let Some(index) = self
+                .delimiter_stack
+                .iter()
+                .rposition(|&(expected_close, _)| expected_close == found_close)
+            {
+                let (_, candidate_open_span) = self.delimiter_stack[index];
+                self.delimiter_stack.truncate(index);
+                return self.error_at(
+                    close_token.span,
+                    format!(
+                        "unmatched brace: `{}` closes the delimiter opened at {}, not the one opened at {}",
+                        found_close.text(),
+                        FromTextErrorLocation::from(candidate_open_span),
+                        FromTextErrorLocation::from(open_span),
+                    ),
+                );
+            }
+        }
+        self.error_at(close_token.span, missing_close_error_msg)
     }
 }
 
@@ -1410,6 +2742,53 @@ pub trait FromText<'g>: FromToTextListForm {
     }
     /// do the actual parsing work
     fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self::Parsed, FromTextError>;
+    /// like `parse`, but instead of stopping at the first error, records every diagnostic
+    /// reported through `FromTextState::error_at`/`warn_at` and keeps parsing past it.
+    ///
+    /// Returns `Ok` only if parsing ran to completion *and* no `Error`-severity diagnostic was
+    /// collected along the way (any `Warning`s are simply discarded in that case -- use
+    /// [`parse_collect_diagnostics`](Self::parse_collect_diagnostics) to keep them). Otherwise
+    /// returns every diagnostic collected, `Warning`s included -- either because an `Error` was
+    /// recorded, or because parsing couldn't recover from one at all (e.g. it occurred
+    /// somewhere outside of a `recover_to_safe_boundary` call site).
+    fn parse_collect_errors(
+        file_name: impl Borrow<str>,
+        text: impl Borrow<str>,
+        global_state: &'g GlobalState<'g>,
+    ) -> Result<Self::Parsed, Vec<FromTextError>> {
+        let (retval, diagnostics) = Self::parse_collect_diagnostics(file_name, text, global_state);
+        match retval {
+            Some(parsed) if !diagnostics.iter().any(|d| d.severity == Severity::Error) => {
+                Ok(parsed)
+            }
+            _ => Err(diagnostics),
+        }
+    }
+    /// the shared implementation behind [`parse_collect_errors`](Self::parse_collect_errors):
+    /// parses in error-recovery mode and returns every diagnostic collected (both severities)
+    /// alongside `Some(parsed)` if parsing ran to completion, or `None` if it couldn't recover
+    /// from a diagnostic at all (e.g. one raised outside of a `recover_to_safe_boundary` call
+    /// site)
+    fn parse_collect_diagnostics(
+        file_name: impl Borrow<str>,
+        text: impl Borrow<str>,
+        global_state: &'g GlobalState<'g>,
+    ) -> (Option<Self::Parsed>, Vec<FromTextError>) {
+        let file_name = file_name.borrow();
+        let text = text.borrow();
+        let source_code = FromTextSourceCode::new(file_name, text);
+        let mut state = FromTextState::new(&source_code, global_state);
+        state.recovering_errors = Some(Vec::new());
+        let retval = Self::from_text(&mut state).ok();
+        if retval.is_some() {
+            if let Ok(token) = state.peek_token() {
+                if !token.kind.is_end_of_file() {
+                    let _ = state.error_at_peek_token("extra tokens at end");
+                }
+            }
+        }
+        (retval, state.recovering_errors.take().unwrap_or_default())
+    }
 }
 
 /// a name plus the integer suffix
@@ -1571,6 +2950,17 @@ impl<'g, T: NameMapGetName<'g>> NameMap<'g, T> {
 pub struct ToTextState<'g, 'w> {
     indent: usize,
     at_start_of_line: bool,
+    /// the display column of the next character [`Self::write_str`] would write, used by
+    /// [`ListForm::list_to_text`]'s adaptive layout to decide how much room is left on the
+    /// current line
+    column: usize,
+    /// the target line width [`ListForm::list_to_text`]'s adaptive layout tries to stay under
+    target_width: usize,
+    /// while `Some`, [`Self::write_str`] appends to this buffer instead of calling
+    /// `base_writer` -- lets [`ListForm::list_to_text`] render an item's text once and
+    /// measure/replay it, rather than invoking `ToText::to_text` a second time (which would
+    /// also duplicate its name-allocation side effects) just to find out how wide it is
+    capture_buffer: Option<String>,
     base_writer: &'w mut dyn FnMut(&str) -> fmt::Result,
     values: NameMap<'g, Value<'g>>,
     blocks: NameMap<'g, BlockData<'g>>,
@@ -1578,6 +2968,15 @@ pub struct ToTextState<'g, 'w> {
     functions: NameMap<'g, FunctionData<'g>>,
     struct_type_ids: HashMap<StructType<'g>, usize>,
     is_fragment: bool,
+    verbose: bool,
+    /// the `indent()` depth at which each block's body is being written, recorded
+    /// by `Block::to_text` as it's printed. Used in verbose mode to compute how
+    /// many enclosing scopes a `BreakBlock` exits.
+    block_depths: HashMap<IdRef<'g, BlockData<'g>>, usize>,
+    /// the `indent()` depth at which each loop's header is being written, recorded
+    /// by `Loop::to_text` as it's printed. Used in verbose mode to compute how
+    /// many enclosing scopes a `ContinueLoop` crosses back through.
+    loop_depths: HashMap<IdRef<'g, LoopData<'g>>, usize>,
 }
 
 impl<'g, 'w> ToTextState<'g, 'w> {
@@ -1588,6 +2987,31 @@ impl<'g, 'w> ToTextState<'g, 'w> {
     pub fn is_fragment(&self) -> bool {
         self.is_fragment
     }
+    /// true if `ToText` implementations should augment their normal output with
+    /// inline `#`-comments carrying otherwise-implicit semantic detail (resolved
+    /// types, branch distances, terminator markers). Set via `ToText::display_verbose`.
+    /// Comments use the lexer's line-comment syntax, so `FromText::parse` of the
+    /// result still succeeds -- verbose output is only for human inspection.
+    pub fn is_verbose(&self) -> bool {
+        self.verbose
+    }
+    /// write a verbose-mode-only inline comment. Does nothing unless `is_verbose()`.
+    /// `text` must not contain a newline, since `#` comments run to end of line.
+    pub fn write_verbose_comment(&mut self, text: fmt::Arguments) -> fmt::Result {
+        if self.verbose {
+            write!(self, " # {}", text)?;
+        }
+        Ok(())
+    }
+    /// assert that `value` is a name being defined for the first time (`NewOrOld::New`), e.g.
+    /// a block or value appearing in `ToText` output at its own definition site.
+    ///
+    /// this still `panic!`s, rather than returning a [`Diagnostic`], unlike
+    /// [`FromTextState`]'s error methods: `ToTextState` never parses source text, so it has no
+    /// span to attach to a `Diagnostic` in the first place -- `NameMap` only tracks which
+    /// `IdRef`s have had a name allocated for them yet, which is a `ToText`-side bookkeeping
+    /// detail, not something traceable back to an original parse location. Currently unused
+    /// (no `ToText` impl calls this yet), kept for when one does.
     pub(crate) fn check_name_definition<T>(&self, value: NewOrOld<T>, error_message: &str) -> T {
         match value {
             NewOrOld::New(v) => v,
@@ -1600,6 +3024,9 @@ impl<'g, 'w> ToTextState<'g, 'w> {
             }
         }
     }
+    /// the `NewOrOld::Old` counterpart to [`check_name_definition`](Self::check_name_definition)
+    /// -- assert that `value` is a name that was already defined (a use site, not a definition).
+    /// See that method's doc comment for why this panics instead of returning a `Diagnostic`.
     pub(crate) fn check_name_use<T>(&self, value: NewOrOld<T>, error_message: &str) -> T {
         match value {
             NewOrOld::Old(v) => v,
@@ -1615,10 +3042,22 @@ impl<'g, 'w> ToTextState<'g, 'w> {
     pub(crate) fn new(
         base_writer: &'w mut dyn FnMut(&str) -> fmt::Result,
         is_fragment: bool,
+    ) -> Self {
+        Self::with_verbose(base_writer, is_fragment, false)
+    }
+    /// the default target line width used by [`ListForm::list_to_text`]'s adaptive layout
+    pub const DEFAULT_TARGET_WIDTH: usize = 100;
+    pub(crate) fn with_verbose(
+        base_writer: &'w mut dyn FnMut(&str) -> fmt::Result,
+        is_fragment: bool,
+        verbose: bool,
     ) -> Self {
         ToTextState {
             indent: 0,
             at_start_of_line: true,
+            column: 0,
+            target_width: Self::DEFAULT_TARGET_WIDTH,
+            capture_buffer: None,
             base_writer,
             values: NameMap::new(),
             blocks: NameMap::new(),
@@ -1626,8 +3065,60 @@ impl<'g, 'w> ToTextState<'g, 'w> {
             functions: NameMap::new(),
             struct_type_ids: HashMap::new(),
             is_fragment,
+            verbose,
+            block_depths: HashMap::new(),
+            loop_depths: HashMap::new(),
         }
     }
+    /// the current `indent()` nesting depth
+    pub(crate) fn current_indent(&self) -> usize {
+        self.indent
+    }
+    /// the display column of the next character [`Self::write_str`] would write
+    pub(crate) fn current_column(&self) -> usize {
+        self.column
+    }
+    /// the target line width [`ListForm::list_to_text`]'s adaptive layout tries to stay under
+    pub(crate) fn target_width(&self) -> usize {
+        self.target_width
+    }
+    /// render `f`'s writes into a fresh in-memory buffer instead of the real output, returning
+    /// what was written; `f` sees `self` positioned at the start of an empty line at the
+    /// current `indent()` depth, so any multi-line content it writes is indented consistently
+    /// with wherever the caller ends up actually placing the returned text
+    pub(crate) fn capture(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> fmt::Result,
+    ) -> Result<String, fmt::Error> {
+        let saved_buffer = self.capture_buffer.replace(String::new());
+        let saved_at_start_of_line = mem::replace(&mut self.at_start_of_line, true);
+        let saved_column = mem::replace(&mut self.column, 0);
+        let result = f(self);
+        let captured = mem::replace(&mut self.capture_buffer, saved_buffer).unwrap_or_default();
+        self.at_start_of_line = saved_at_start_of_line;
+        self.column = saved_column;
+        result?;
+        Ok(captured)
+    }
+    /// record that `block`'s body is being written at the current `indent()` depth
+    pub(crate) fn record_block_depth(&mut self, block: IdRef<'g, BlockData<'g>>) {
+        self.block_depths.insert(block, self.indent);
+    }
+    /// record that `loop_`'s header is being written at the current `indent()` depth
+    pub(crate) fn record_loop_depth(&mut self, loop_: IdRef<'g, LoopData<'g>>) {
+        self.loop_depths.insert(loop_, self.indent);
+    }
+    /// the number of enclosing scopes a `BreakBlock` targeting `block` would exit,
+    /// if `block`'s depth has been recorded yet (it always has by the time a
+    /// well-formed program's `BreakBlock` is printed, since the target must be an
+    /// already-open enclosing block)
+    pub(crate) fn block_break_distance(&self, block: IdRef<'g, BlockData<'g>>) -> Option<usize> {
+        Some(self.indent - *self.block_depths.get(&block)?)
+    }
+    /// the number of enclosing scopes a `ContinueLoop` targeting `loop_` would cross
+    pub(crate) fn loop_continue_distance(&self, loop_: IdRef<'g, LoopData<'g>>) -> Option<usize> {
+        Some(self.indent - *self.loop_depths.get(&loop_)?)
+    }
     pub(crate) fn get_value_named_id(
         &mut self,
         value: IdRef<'g, Value<'g>>,
@@ -1688,13 +3179,27 @@ impl<'g, 'w> ToTextState<'g, 'w> {
     }
 }
 
+impl ToTextState<'_, '_> {
+    /// send `text` (which must not contain `'\n'`) to wherever output is currently headed --
+    /// the real `base_writer`, or a [`Self::capture`] buffer if one is active
+    fn emit_str(&mut self, text: &str) -> fmt::Result {
+        if let Some(buffer) = &mut self.capture_buffer {
+            buffer.push_str(text);
+            Ok(())
+        } else {
+            (self.base_writer)(text)
+        }
+    }
+}
+
 impl fmt::Write for ToTextState<'_, '_> {
     fn write_str(&mut self, text: &str) -> fmt::Result {
         let mut first = true;
         for text in text.split('\n') {
             if !mem::replace(&mut first, false) {
-                (self.base_writer)("\n")?;
+                self.emit_str("\n")?;
                 self.at_start_of_line = true;
+                self.column = 0;
             }
             if text.is_empty() {
                 continue;
@@ -1727,13 +3232,15 @@ impl fmt::Write for ToTextState<'_, '_> {
                 // write in larger chunks to speed-up output
 
                 let mut indent = self.indent * Self::INDENT_MULTIPLE;
+                self.column += indent;
                 while indent >= SPACES.len() {
-                    (self.base_writer)(SPACES)?;
+                    self.emit_str(SPACES)?;
                     indent -= SPACES.len();
                 }
-                (self.base_writer)(&SPACES[..indent])?;
+                self.emit_str(&SPACES[..indent])?;
             }
-            (self.base_writer)(text)?;
+            self.emit_str(text)?;
+            self.column += text.chars().count();
         }
         Ok(())
     }
@@ -1764,29 +3271,132 @@ pub struct ListForm {
     ///
     /// If this is set, then lists like `[a, b, c]` are not allowed because there isn't a `,` after the `c`.
     pub final_item_terminator_required: bool,
-    /// If the final item terminator is produced when converting to text
+    /// If the final item terminator is produced when converting to text, for a list laid out
+    /// broken across multiple lines.
     ///
-    /// If this is set, then lists are produced like `[a, b, c,]` instead of like `[a, b, c]`.
+    /// `list_to_text` lays a list out adaptively: short lists stay on one line (`[a, b, c]`),
+    /// long ones break one item per line. A one-line list never shows a final terminator
+    /// (`self.final_item_terminator_required` aside); a broken one shows it when this flag is
+    /// set, matching the common convention of a trailing comma on every line of a broken list
+    /// (so a line can be added or removed without disturbing any other line's punctuation).
     pub final_item_terminator_displayed: bool,
-    /// If the list, when converted to text, is spread over multiple lines instead of all on one line.
-    ///
-    /// If this is set, then lists are produced like:
-    /// ```text
-    /// {
-    ///     a;
-    ///     b;
-    ///     c;
-    /// }
-    /// ```
-    /// instead of:
-    /// ```text
-    /// {a; b; c;}
-    /// ```
-    pub multi_line: bool,
+}
+
+/// a Wadler/Leijen-style pretty-printing document, used by
+/// [`ListForm::list_to_text_with_extra_callbacks`] to lay a list out adaptively: flat if it
+/// fits within [`ToTextState::target_width`], broken one item per line otherwise
+///
+/// each [`ListForm::list_to_text_with_extra_callbacks`] call builds and renders its own `Doc`
+/// covering just that one list; a nested list's own call independently builds, measures, and
+/// renders its own `Doc` -- by the time the outer list's `Group` is measured, a nested list
+/// it contains has already finished rendering to plain text, so nesting doesn't need its own
+/// special case anywhere below.
+enum Doc {
+    /// literal already-rendered text (may itself contain `'\n'`, e.g. a nested list that broke)
+    Text(String),
+    /// a break point: a single space in [`Mode::Flat`], a newline followed by the current
+    /// indent in [`Mode::Break`]
+    Line,
+    /// `text` only in [`Mode::Break`]; omitted entirely in [`Mode::Flat`]
+    IfBreak(&'static str),
+    /// indent everything inside `doc` by 1 unit while rendering in [`Mode::Break`]
+    Nest(Box<Doc>),
+    /// a sequence of documents, laid out one after another
+    Concat(Vec<Doc>),
+    /// rendered entirely in [`Mode::Flat`] if it fits in the remaining width, else entirely in
+    /// [`Mode::Break`]
+    Group(Box<Doc>),
+}
+
+/// whether a [`Doc`] is being laid out on one line or broken across several
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// `true` if `docs` can be rendered starting at `column`, in the modes given, without any
+/// of it exceeding `target_width` before the next forced line break (a [`Doc::Line`] in
+/// [`Mode::Break`], or a `'\n'` embedded in a [`Doc::Text`])
+fn fits(mut column: usize, target_width: usize, mut docs: Vec<(Mode, &Doc)>) -> bool {
+    loop {
+        if column > target_width {
+            return false;
+        }
+        let (mode, doc) = match docs.pop() {
+            Some(entry) => entry,
+            None => return true,
+        };
+        match doc {
+            Doc::Text(text) => match text.find('\n') {
+                Some(first_line_len) => return column + first_line_len <= target_width,
+                None => column += text.chars().count(),
+            },
+            Doc::Line => match mode {
+                Mode::Flat => column += 1,
+                Mode::Break => return true,
+            },
+            Doc::IfBreak(_text) => {
+                if mode == Mode::Break {
+                    return true;
+                }
+            }
+            Doc::Nest(doc) => docs.push((mode, doc.as_ref())),
+            Doc::Concat(parts) => docs.extend(parts.iter().rev().map(|part| (mode, part))),
+            Doc::Group(doc) => docs.push((Mode::Flat, doc.as_ref())),
+        }
+    }
+}
+
+/// write `doc` to `state`, picking `Mode::Flat` or `Mode::Break` for each [`Doc::Group`] it
+/// contains by running [`fits`] against the room left on the current line
+fn render(state: &mut ToTextState<'_, '_>, doc: &Doc, mode: Mode) -> fmt::Result {
+    match doc {
+        Doc::Text(text) => state.write_str(text),
+        Doc::Line => match mode {
+            Mode::Flat => state.write_str(" "),
+            Mode::Break => state.write_str("\n"),
+        },
+        Doc::IfBreak(text) => {
+            if mode == Mode::Break {
+                state.write_str(text)
+            } else {
+                Ok(())
+            }
+        }
+        Doc::Nest(doc) => {
+            state.indent += 1;
+            let result = render(state, doc.as_ref(), mode);
+            state.indent -= 1;
+            result
+        }
+        Doc::Concat(parts) => {
+            for part in parts {
+                render(state, part, mode)?;
+            }
+            Ok(())
+        }
+        Doc::Group(doc) => {
+            let fits_on_line = fits(
+                state.current_column(),
+                state.target_width(),
+                vec![(Mode::Flat, doc.as_ref())],
+            );
+            let group_mode = if fits_on_line { Mode::Flat } else { Mode::Break };
+            render(state, doc.as_ref(), group_mode)
+        }
+    }
 }
 
 impl ListForm {
-    /// use `[a, b, c]`
+    /// use `[a, b, c]`, or, broken across lines because it's too long to fit on one:
+    /// ```text
+    /// [
+    ///     a,
+    ///     b,
+    ///     c,
+    /// ]
+    /// ```
     pub const SQUARE_BRACKETS: ListForm = ListForm {
         opening_punct: Punctuation::LSquareBracket,
         opening_punct_missing_msg: "missing opening square bracket (`[`)",
@@ -1795,10 +3405,9 @@ impl ListForm {
         item_terminator: Punctuation::Comma,
         item_terminator_missing_msg: "missing comma after item (`,`)",
         final_item_terminator_required: false,
-        final_item_terminator_displayed: false,
-        multi_line: false,
+        final_item_terminator_displayed: true,
     };
-    /// use:
+    /// use `{a; b; c;}`, or, broken across lines because it's too long to fit on one:
     /// ```text
     /// {
     ///     a;
@@ -1815,7 +3424,24 @@ impl ListForm {
         item_terminator_missing_msg: "missing semicolon after item (`;`)",
         final_item_terminator_required: true,
         final_item_terminator_displayed: true,
-        multi_line: true,
+    };
+    /// use `{a, b, c}`, or, broken across lines because it's too long to fit on one:
+    /// ```text
+    /// {
+    ///     a,
+    ///     b,
+    ///     c,
+    /// }
+    /// ```
+    pub const CURLY_BRACES: ListForm = ListForm {
+        opening_punct: Punctuation::LCurlyBrace,
+        opening_punct_missing_msg: "missing opening curly brace (`{`)",
+        closing_punct: Punctuation::RCurlyBrace,
+        closing_punct_missing_msg: "missing closing curly brace (`}`)",
+        item_terminator: Punctuation::Comma,
+        item_terminator_missing_msg: "missing comma after item (`,`)",
+        final_item_terminator_required: false,
+        final_item_terminator_displayed: true,
     };
     /// parse a list, calling `parse_item` to parse each item in the list
     pub fn parse_list<'g, 'a>(
@@ -1827,6 +3453,12 @@ impl ListForm {
     }
     /// parse a list, calling `after_opening_punct` after parsing `self.opening_punct`,
     /// calling `parse_item` to parse each item in the list
+    ///
+    /// while running under [`FromText::parse_collect_errors`] (`state.is_recovering_errors()`),
+    /// a `parse_item` that fails doesn't abort the whole list: its error was already recorded,
+    /// so this skips tokens up to the next `self.item_terminator` or `self.closing_punct` and
+    /// keeps going with the next item, the same synchronize-and-continue approach
+    /// `recover_to_safe_boundary` uses for statements
     pub fn parse_list_with_extra_callbacks<'g, 'a, R>(
         self,
         state: &mut FromTextState<'g, 'a>,
@@ -1844,7 +3476,18 @@ impl ListForm {
                     if state.peek_token()?.kind.punct() == Some(self.closing_punct) {
                         break;
                     }
-                    parse_item(state)?;
+                    match parse_item(state) {
+                        Ok(()) => {}
+                        // the error was already recorded by the `error_at` call that produced
+                        // it -- resynchronize to this list's own terminator/closing
+                        // punctuation (rather than bailing the whole parse) and pick back up
+                        // with the next item
+                        Err(_) if state.is_recovering_errors() => {
+                            state.recover_to_list_boundary(self.item_terminator, self.closing_punct);
+                            continue;
+                        }
+                        Err(error) => return Err(error),
+                    }
                     let peek_punct = state.peek_token()?.kind.punct();
                     if peek_punct == Some(self.closing_punct) {
                         if self.final_item_terminator_required {
@@ -1933,6 +3576,11 @@ impl ListForm {
     }
     /// write a list, calling `after_opening_punct` after writing `self.opening_punct`,
     /// calling `item_to_text` to write each item in the list
+    ///
+    /// lays the list out adaptively against `state.target_width()`: stays on one line if it
+    /// fits, otherwise breaks to one item per line (see [`Doc`]/[`render`]). Each item is
+    /// rendered exactly once, via [`ToTextState::capture`], so `item_to_text` never runs
+    /// twice over the same item just to measure it.
     pub fn list_to_text_with_extra_callbacks<'g, 'a, Item>(
         self,
         state: &mut ToTextState<'g, 'a>,
@@ -1940,29 +3588,33 @@ impl ListForm {
         mut item_to_text: impl FnMut(&mut ToTextState<'g, 'a>, Item) -> fmt::Result,
         items: impl IntoIterator<Item = Item>,
     ) -> fmt::Result {
-        let write_body = |state: &mut ToTextState<'g, 'a>| -> fmt::Result {
-            after_opening_punct(state)?;
-            let mut items = items.into_iter().peekable();
-            while let Some(item) = items.next() {
-                item_to_text(state, item)?;
-                if self.final_item_terminator_displayed || items.peek().is_some() {
-                    write!(state, "{}", self.item_terminator)?;
-                }
-                if self.multi_line {
-                    writeln!(state)?;
-                } else if items.peek().is_some() {
-                    write!(state, " ")?;
-                }
+        write!(state, "{}", self.opening_punct)?;
+        after_opening_punct(state)?;
+        let item_docs = items
+            .into_iter()
+            .map(|item| Ok(Doc::Text(state.capture(|state| item_to_text(state, item))?)))
+            .collect::<Result<Vec<_>, fmt::Error>>()?;
+        let item_count = item_docs.len();
+        let mut nested = Vec::with_capacity(item_count * 3 + 1);
+        if item_count != 0 {
+            nested.push(Doc::IfBreak("\n"));
+        }
+        for (index, item_doc) in item_docs.into_iter().enumerate() {
+            nested.push(item_doc);
+            if index + 1 != item_count {
+                nested.push(Doc::Text(self.item_terminator.text().to_string()));
+                nested.push(Doc::Line);
+            } else if self.final_item_terminator_required {
+                nested.push(Doc::Text(self.item_terminator.text().to_string()));
+            } else if self.final_item_terminator_displayed {
+                nested.push(Doc::IfBreak(self.item_terminator.text()));
             }
-            Ok(())
-        };
-        if self.multi_line {
-            writeln!(state, "{}", self.opening_punct)?;
-            state.indent(write_body)?;
-        } else {
-            write!(state, "{}", self.opening_punct)?;
-            write_body(state)?;
         }
+        let mut doc = vec![Doc::Nest(Box::new(Doc::Concat(nested)))];
+        if item_count != 0 {
+            doc.push(Doc::IfBreak("\n"));
+        }
+        render(state, &Doc::Group(Box::new(Doc::Concat(doc))), Mode::Flat)?;
         write!(state, "{}", self.closing_punct)
     }
 }
@@ -1989,7 +3641,15 @@ pub trait ToText<'g>: FromToTextListForm {
     ///
     /// should not be used from `ToText` implementations, `ToText::to_text` should instead be called.
     fn display(&self) -> ToTextDisplay<'g, '_, Self> {
-        ToTextDisplay::new(self, true)
+        ToTextDisplay::new(self, true, false)
+    }
+    /// like `display`, but augments the output with inline `#`-comments
+    /// carrying otherwise-implicit semantic detail -- see
+    /// `ToTextState::is_verbose` for what gets annotated. The result still
+    /// round-trips through `FromText::parse` since the comments use the
+    /// lexer's line-comment syntax.
+    fn display_verbose(&self) -> ToTextDisplay<'g, '_, Self> {
+        ToTextDisplay::new(self, true, true)
     }
     /// convert `self` to text.
     ///
@@ -2001,14 +3661,16 @@ pub trait ToText<'g>: FromToTextListForm {
 pub struct ToTextDisplay<'g, 'a, T: ToText<'g> + ?Sized> {
     value: &'a T,
     is_fragment: bool,
+    verbose: bool,
     _phantom: PhantomData<&'g ()>,
 }
 
 impl<'g, 'a, T: ToText<'g> + ?Sized> ToTextDisplay<'g, 'a, T> {
-    pub(crate) fn new(value: &'a T, is_fragment: bool) -> Self {
+    pub(crate) fn new(value: &'a T, is_fragment: bool, verbose: bool) -> Self {
         Self {
             value,
             is_fragment,
+            verbose,
             _phantom: PhantomData,
         }
     }
@@ -2016,9 +3678,10 @@ impl<'g, 'a, T: ToText<'g> + ?Sized> ToTextDisplay<'g, 'a, T> {
 
 impl<'g, T: ToText<'g> + ?Sized> fmt::Display for ToTextDisplay<'g, '_, T> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        self.value.to_text(&mut ToTextState::new(
+        self.value.to_text(&mut ToTextState::with_verbose(
             &mut |text: &str| formatter.write_str(text),
             self.is_fragment,
+            self.verbose,
         ))
     }
 }
@@ -6,6 +6,7 @@ use crate::text::FromTextError;
 use crate::text::FromTextState;
 use crate::text::IntegerToken;
 use crate::text::Keyword;
+use crate::text::ListForm;
 use crate::text::Punctuation;
 use crate::text::ToTextState;
 use crate::text::TokenKind;
@@ -13,6 +14,7 @@ use std::convert::TryInto;
 use std::fmt;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::vec::Vec;
 
 pub trait GenericType<'g>: Internable<'g, Interned = Type<'g>> {
     fn undef(&self, global_state: &'g GlobalState<'g>) -> Const<'g> {
@@ -35,6 +37,8 @@ pub enum IntegerType {
     Int8,
     Int16,
     Int32,
+    /// a 32-bit integer, but see `ConstInteger::RelaxedInt32`
+    RelaxedInt32,
     Int64,
 }
 
@@ -57,6 +61,8 @@ impl From<IntegerType> for Type<'_> {
 pub enum FloatType {
     Float16,
     Float32,
+    /// a 32-bit float, but see `ConstFloat::RelaxedFloat32`
+    RelaxedFloat32,
     Float64,
 }
 
@@ -171,6 +177,48 @@ impl<'g> From<OpaqueType<'g>> for Type<'g> {
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ArrayType<'g> {
+    pub len: usize,
+    pub element: Interned<'g, Type<'g>>,
+}
+
+impl<'g> Internable<'g> for ArrayType<'g> {
+    type Interned = Type<'g>;
+    fn intern(&self, global_state: &'g GlobalState<'g>) -> Interned<'g, Type<'g>> {
+        Type::from(*self).intern(global_state)
+    }
+}
+
+impl<'g> GenericType<'g> for ArrayType<'g> {}
+
+impl<'g> From<ArrayType<'g>> for Type<'g> {
+    fn from(v: ArrayType<'g>) -> Self {
+        Type::Array(v)
+    }
+}
+
+/// an ordered, possibly-heterogeneous aggregate type
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct StructType<'g> {
+    pub members: Vec<Interned<'g, Type<'g>>>,
+}
+
+impl<'g> Internable<'g> for StructType<'g> {
+    type Interned = Type<'g>;
+    fn intern(&self, global_state: &'g GlobalState<'g>) -> Interned<'g, Type<'g>> {
+        Type::from(self.clone()).intern(global_state)
+    }
+}
+
+impl<'g> GenericType<'g> for StructType<'g> {}
+
+impl<'g> From<StructType<'g>> for Type<'g> {
+    fn from(v: StructType<'g>) -> Self {
+        Type::Struct(v)
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub enum Type<'g> {
     Integer(IntegerType),
@@ -178,6 +226,8 @@ pub enum Type<'g> {
     Bool(BoolType),
     Pointer(PointerType<'g>),
     Vector(VectorType<'g>),
+    Array(ArrayType<'g>),
+    Struct(StructType<'g>),
     Opaque(OpaqueType<'g>),
 }
 
@@ -282,6 +332,7 @@ impl_from_to_text_for_keyword_type! {
         I8 => IntegerType::Int8,
         I16 => IntegerType::Int16,
         I32 => IntegerType::Int32,
+        RI32 => IntegerType::RelaxedInt32,
         I64 => IntegerType::Int64,
         _ => "invalid integer type",
     }
@@ -291,6 +342,7 @@ impl_from_to_text_for_keyword_type! {
     FloatType {
         F16 => FloatType::Float16,
         F32 => FloatType::Float32,
+        RF32 => FloatType::RelaxedFloat32,
         F64 => FloatType::Float64,
         _ => "invalid float type",
     }
@@ -368,6 +420,69 @@ impl<'g> ToText<'g> for VectorType<'g> {
     }
 }
 
+impl<'g> FromText<'g> for ArrayType<'g> {
+    type Parsed = Self;
+    fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
+        state.parse_parenthesized(
+            Punctuation::LSquareBracket,
+            "missing opening square bracket: '['",
+            Punctuation::RSquareBracket,
+            "missing closing square bracket: ']'",
+            |state| -> Result<ArrayType<'g>, FromTextError> {
+                let len = state.parse_token()?;
+                let len: usize = match len.kind {
+                    TokenKind::Integer(IntegerToken { value, suffix }) => {
+                        if suffix.is_some() {
+                            state.error_at(
+                                len.span,
+                                "array length value must not have type suffix",
+                            )?;
+                        }
+                        match value.try_into() {
+                            Ok(len) => len,
+                            Err(_) => state
+                                .error_at(len.span, "array length value too big")?
+                                .into(),
+                        }
+                    }
+                    _ => state
+                        .error_at(len.span, "missing array length value")?
+                        .into(),
+                };
+                state.parse_keyword_token_or_error(Keyword::X, "missing x after array length")?;
+                Ok(ArrayType {
+                    len,
+                    element: Type::from_text(state)?,
+                })
+            },
+        )
+    }
+}
+
+impl<'g> ToText<'g> for ArrayType<'g> {
+    fn to_text(&self, state: &mut ToTextState<'g, '_>) -> fmt::Result {
+        write!(state, "[{} x ", self.len)?;
+        self.element.to_text(state)?;
+        write!(state, "]")
+    }
+}
+
+impl<'g> FromText<'g> for StructType<'g> {
+    type Parsed = Self;
+    fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
+        state.parse_keyword_token_or_error(Keyword::Struct, "expected struct type")?;
+        let members = ListForm::CURLY_BRACES.parse_vec(state, Type::from_text)?;
+        Ok(StructType { members })
+    }
+}
+
+impl<'g> ToText<'g> for StructType<'g> {
+    fn to_text(&self, state: &mut ToTextState<'g, '_>) -> fmt::Result {
+        write!(state, "struct ")?;
+        ListForm::CURLY_BRACES.list_to_text(state, self.members.iter().copied())
+    }
+}
+
 impl<'g> FromText<'g> for PointerType<'g> {
     type Parsed = Self;
     fn from_text(state: &mut FromTextState<'g, '_>) -> Result<Self, FromTextError> {
@@ -427,6 +542,12 @@ impl<'g> FromText<'g> for Type<'g> {
                 );
             }
             TokenKind::Punct(Punctuation::LessThan) => Type::Vector(VectorType::from_text(state)?),
+            TokenKind::Punct(Punctuation::LSquareBracket) => {
+                Type::Array(ArrayType::from_text(state)?)
+            }
+            TokenKind::Keyword(Keyword::RI32) => Type::Integer(IntegerType::from_text(state)?),
+            TokenKind::Keyword(Keyword::RF32) => Type::Float(FloatType::from_text(state)?),
+            TokenKind::Keyword(Keyword::Struct) => Type::Struct(StructType::from_text(state)?),
             TokenKind::Punct(Punctuation::Asterisk) => {
                 Type::Pointer(PointerType::from_text(state)?)
             }
@@ -445,6 +566,8 @@ impl<'g> ToText<'g> for Type<'g> {
             Type::Bool(v) => v.to_text(state),
             Type::Pointer(v) => v.to_text(state),
             Type::Vector(v) => v.to_text(state),
+            Type::Array(v) => v.to_text(state),
+            Type::Struct(v) => v.to_text(state),
             Type::Opaque(v) => v.to_text(state),
         }
     }
@@ -481,9 +604,11 @@ mod tests {
         test_type!(global_state, "i8", IntegerType::Int8);
         test_type!(global_state, "i16", IntegerType::Int16);
         test_type!(global_state, "i32", IntegerType::Int32);
+        test_type!(global_state, "ri32", IntegerType::RelaxedInt32);
         test_type!(global_state, "i64", IntegerType::Int64);
         test_type!(global_state, "f16", FloatType::Float16);
         test_type!(global_state, "f32", FloatType::Float32);
+        test_type!(global_state, "rf32", FloatType::RelaxedFloat32);
         test_type!(global_state, "f64", FloatType::Float64);
         test_type!(global_state, "bool", BoolType);
         test_type!(
@@ -522,6 +647,26 @@ mod tests {
             },
             "<vscale x 7 x *bool>"
         );
+        test_type!(
+            global_state,
+            "[4 x f16]",
+            ArrayType {
+                len: 4,
+                element: FloatType::Float16.intern(&global_state)
+            }
+        );
+        test_type!(
+            global_state,
+            "struct {i8, f32}",
+            StructType {
+                members: vec![
+                    IntegerType::Int8.intern(&global_state),
+                    FloatType::Float32.intern(&global_state),
+                ]
+            },
+            "struct {i8, f32}"
+        );
+        test_type!(global_state, "struct {}", StructType { members: vec![] });
         // FIXME: add tests for opaque types
     }
 }
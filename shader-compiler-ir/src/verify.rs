@@ -0,0 +1,289 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! structural and SSA verification of control-flow IR
+//!
+//! the `FromText`/`ToText` impls in `block.rs` assume but never check that a
+//! `Block`'s body ends in exactly one terminator, that `break`/`continue`
+//! targets are in scope with matching argument arity and types, and that
+//! every value use is dominated by its definition. `verify` makes those
+//! assumptions into a checkable property instead of an implicit one: it's
+//! run automatically by `Block`'s `FromText::parse` in debug builds (see
+//! `block.rs`), so malformed input is rejected with a
+//! precise location rather than silently constructing a broken tree.
+//!
+//! Dominance is checked by threading an accumulated `defined_so_far` set
+//! down through the `Block`/`Loop` nesting rather than building a separate
+//! control-flow graph and running the iterative Cooper-Harvey-Kennedy
+//! algorithm over it: `break`/`continue` can only target a region already
+//! on `Verifier::enclosing` (there's no instruction that branches to an
+//! arbitrary, not-yet-seen label), so this IR's structured nesting already
+//! *is* its dominator tree -- a value dominates a use exactly when it was
+//! defined earlier in the same body or in a body lexically enclosing it,
+//! which is what `verify_body`/`verify_block` compute in one pass. A
+//! generic CFG-and-dominator-tree pass would be solving a harder problem
+//! than this tree's control-flow shape poses.
+//!
+//! `BranchInstruction` (referenced in `generated_instructions.rs` and in
+//! `tests/debug-test.rs`, neither of which matches this module's current
+//! `Loop`/`BreakBlock`/`ContinueLoop`-based control flow) isn't defined
+//! anywhere in this source tree, so "a `BranchInstruction` could target a
+//! block it can't reach" isn't checkable here; `check_break_block` and
+//! `check_continue_loop` below cover the equivalent checks for the
+//! terminators that do exist.
+
+use crate::block::{BlockData, BlockRef, BreakBlock, ContinueLoop, Loop, LoopData, LoopRef};
+use crate::prelude::*;
+use crate::text::FromTextErrorLocation;
+use crate::Instruction;
+use std::fmt;
+
+/// an error produced by `verify`
+#[derive(Clone, Debug)]
+pub struct VerifierError {
+    /// the location of the instruction that failed verification, if known
+    pub location: Option<FromTextErrorLocation>,
+    /// the description of the error
+    pub message: String,
+}
+
+impl fmt::Display for VerifierError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{}: verify error: {}", location, self.message)
+        } else {
+            write!(f, "verify error: {}", self.message)
+        }
+    }
+}
+
+/// one entry in the stack of control-flow regions enclosing the instruction currently being verified
+enum EnclosingRegion<'g, 'a> {
+    Block(&'a BlockData<'g>),
+    Loop(&'a LoopData<'g>),
+}
+
+/// accumulates `VerifierError`s while walking a block/loop tree
+struct Verifier<'g, 'a> {
+    enclosing: Vec<EnclosingRegion<'g, 'a>>,
+    errors: Vec<VerifierError>,
+}
+
+impl<'g, 'a> Verifier<'g, 'a> {
+    fn push_error(&mut self, instruction: &Instruction<'g>, message: impl Into<String>) {
+        self.errors.push(VerifierError {
+            location: instruction.location.map(Into::into),
+            message: message.into(),
+        });
+    }
+    fn check_break_block(&mut self, instruction: &Instruction<'g>, break_block: &BreakBlock<'g>) {
+        let found = self.enclosing.iter().find_map(|region| match region {
+            EnclosingRegion::Block(block) if BlockRef::new(IdRef::from(*block)) == break_block.block => {
+                Some(*block)
+            }
+            _ => None,
+        });
+        let target = match found {
+            Some(target) => target,
+            None => {
+                self.push_error(instruction, "break target is not an enclosing block");
+                return;
+            }
+        };
+        match target.results() {
+            Uninhabited => self.push_error(
+                instruction,
+                "break target block has no result_definitions -- it is unreachable by break",
+            ),
+            Inhabited(result_definitions) => {
+                if result_definitions.len() != break_block.block_results.len() {
+                    self.push_error(
+                        instruction,
+                        format!(
+                            "break argument count mismatch: expected {}, got {}",
+                            result_definitions.len(),
+                            break_block.block_results.len()
+                        ),
+                    );
+                } else {
+                    for (result_definition, block_result) in
+                        result_definitions.iter().zip(&break_block.block_results)
+                    {
+                        if result_definition.value().get_type() != block_result.get_type() {
+                            self.push_error(instruction, "break argument type mismatch");
+                        }
+                    }
+                }
+            }
+        }
+    }
+    fn check_continue_loop(
+        &mut self,
+        instruction: &Instruction<'g>,
+        continue_loop: &ContinueLoop<'g>,
+    ) {
+        let found = self.enclosing.iter().find_map(|region| match region {
+            EnclosingRegion::Loop(loop_) if LoopRef::new(IdRef::from(*loop_)) == continue_loop.target_loop => {
+                Some(*loop_)
+            }
+            _ => None,
+        });
+        let target = match found {
+            Some(target) => target,
+            None => {
+                self.push_error(instruction, "continue target is not an enclosing loop");
+                return;
+            }
+        };
+        let argument_definitions = &target.header.argument_definitions;
+        if argument_definitions.len() != continue_loop.loop_arguments.len() {
+            self.push_error(
+                instruction,
+                format!(
+                    "continue argument count mismatch: expected {}, got {}",
+                    argument_definitions.len(),
+                    continue_loop.loop_arguments.len()
+                ),
+            );
+        } else {
+            for (argument_definition, loop_argument) in
+                argument_definitions.iter().zip(&continue_loop.loop_arguments)
+            {
+                if argument_definition.value().get_type() != loop_argument.get_type() {
+                    self.push_error(instruction, "continue argument type mismatch");
+                }
+            }
+        }
+    }
+    fn check_loop_initial_arguments(&mut self, instruction: &Instruction<'g>, loop_: &Loop<'g>) {
+        let argument_definitions = &loop_.header.argument_definitions;
+        if argument_definitions.len() != loop_.arguments.len() {
+            self.push_error(
+                instruction,
+                format!(
+                    "loop initial argument count mismatch: expected {}, got {}",
+                    argument_definitions.len(),
+                    loop_.arguments.len()
+                ),
+            );
+        } else {
+            for (argument_definition, argument) in
+                argument_definitions.iter().zip(&loop_.arguments)
+            {
+                if argument_definition.value().get_type() != argument.get_type() {
+                    self.push_error(instruction, "loop initial argument type mismatch");
+                }
+            }
+        }
+    }
+    /// checks that every `ValueUse` in `instruction`'s arguments is defined by a
+    /// `ValueDefinition` visible in an enclosing scope or earlier in `defined_so_far`.
+    fn check_dominance(
+        &mut self,
+        instruction: &Instruction<'g>,
+        defined_so_far: &std::collections::HashSet<Value<'g>>,
+    ) {
+        for argument in instruction.arguments() {
+            if !defined_so_far.contains(&argument.value()) {
+                self.push_error(
+                    instruction,
+                    "value used before its definition dominates this use",
+                );
+            }
+        }
+    }
+    /// checks that a terminator (an instruction with `Uninhabited` results)
+    /// appears exactly once, as the body's last instruction -- never earlier,
+    /// and the body never falls off the end without one.
+    fn check_terminator_position(&mut self, body: &'a [Instruction<'g>]) {
+        for (index, instruction) in body.iter().enumerate() {
+            let is_last = index + 1 == body.len();
+            match (instruction.results(), is_last) {
+                (Uninhabited, true) => {}
+                (Uninhabited, false) => {
+                    self.push_error(instruction, "terminator instruction is not at the end of its block");
+                }
+                (Inhabited(_), true) => {
+                    self.push_error(
+                        instruction,
+                        "block falls through without a terminating instruction",
+                    );
+                }
+                (Inhabited(_), false) => {}
+            }
+        }
+        if body.is_empty() {
+            self.errors.push(VerifierError {
+                location: None,
+                message: "block has an empty body with no terminator".to_string(),
+            });
+        }
+    }
+    fn verify_body(
+        &mut self,
+        body: &'a [Instruction<'g>],
+        defined_so_far: &std::collections::HashSet<Value<'g>>,
+    ) {
+        self.check_terminator_position(body);
+        let mut defined_so_far = defined_so_far.clone();
+        for instruction in body {
+            self.check_dominance(instruction, &defined_so_far);
+            if let Some(break_block) = instruction.downcast_ref::<BreakBlock<'g>>() {
+                self.check_break_block(instruction, break_block);
+            } else if let Some(continue_loop) = instruction.downcast_ref::<ContinueLoop<'g>>() {
+                self.check_continue_loop(instruction, continue_loop);
+            } else if let Some(loop_) = instruction.downcast_ref::<Loop<'g>>() {
+                self.check_loop_initial_arguments(instruction, loop_);
+                self.enclosing.push(EnclosingRegion::Loop(&loop_));
+                // the loop body also dominates uses of its own header's argument
+                // definitions, in addition to everything dominating the loop itself
+                let mut loop_defined_so_far = defined_so_far.clone();
+                loop_defined_so_far.extend(
+                    loop_
+                        .header
+                        .argument_definitions
+                        .iter()
+                        .map(ValueDefinition::value),
+                );
+                self.verify_block(&loop_.body, &loop_defined_so_far);
+                self.enclosing.pop();
+            } else if let Some(block) = instruction.downcast_ref::<crate::block::Block<'g>>() {
+                self.verify_block(block, &defined_so_far);
+            }
+            if let Inhabited(result_definitions) = instruction.results() {
+                for result_definition in result_definitions {
+                    defined_so_far.insert(result_definition.value());
+                }
+            }
+        }
+    }
+    fn verify_block(
+        &mut self,
+        block: &'a BlockData<'g>,
+        defined_so_far: &std::collections::HashSet<Value<'g>>,
+    ) {
+        self.enclosing.push(EnclosingRegion::Block(block));
+        if let Some(body) = block.body.get() {
+            self.verify_body(body, defined_so_far);
+        }
+        self.enclosing.pop();
+    }
+}
+
+/// verify the structural invariants of `block`'s control flow that
+/// `FromText`/`ToText` assume but don't enforce: every `break`/`continue`
+/// targets an enclosing block/loop with matching argument arity and types,
+/// every `Loop`'s initial arguments match its header, and every value use
+/// is dominated by its definition.
+pub fn verify<'g>(block: &BlockData<'g>) -> Result<(), Vec<VerifierError>> {
+    let mut verifier = Verifier {
+        enclosing: Vec::new(),
+        errors: Vec::new(),
+    };
+    verifier.verify_block(block, &std::collections::HashSet::new());
+    if verifier.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(verifier.errors)
+    }
+}
@@ -0,0 +1,274 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! Randomized print/parse round-trip testing for the text assembly grammar.
+//!
+//! `debug-test.rs`'s hand-written trees only exercise one shape of `Block`/
+//! `Loop` nesting, so this generates many well-formed random ones instead:
+//! pick a block, append random instructions ending in a terminator, nest
+//! loops/blocks up to a depth bound, and only wire `break`/`continue` to
+//! targets that are actually in scope. Each generation decision is drawn from
+//! a seeded PRNG so a failure can be replayed from just its seed; on panic
+//! the seed and a step-by-step log of those decisions are written next to
+//! the minimized failing snippet so the run reproduces deterministically.
+
+use shader_compiler_ir::prelude::*;
+use shader_compiler_ir::{BreakBlock, ContinueLoop, InstructionData, Loop, LoopHeader, OnceCell};
+use std::fs;
+use std::panic;
+use std::path::Path;
+
+/// number of random trees generated per test run. Kept small enough to run
+/// as part of the normal test suite; increase locally when hunting for a
+/// specific class of bug.
+const ITERATIONS: u64 = 200;
+/// maximum nesting depth of loops/blocks in a generated tree
+const MAX_DEPTH: u32 = 4;
+/// maximum instructions appended to a single body before its terminator
+const MAX_BODY_LEN: u32 = 4;
+
+/// a small deterministic PRNG so a fuzz run (and any failure it finds) is
+/// reproducible from just a `u64` seed, without an external `rand` dependency
+struct Xorshift64(u64);
+
+impl Xorshift64 {
+    fn new(seed: u64) -> Self {
+        Xorshift64(seed | 1)
+    }
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+    fn next_below(&mut self, bound: u32) -> u32 {
+        (self.next_u64() % u64::from(bound.max(1))) as u32
+    }
+    fn next_bool(&mut self, probability_percent: u32) -> bool {
+        self.next_below(100) < probability_percent
+    }
+}
+
+/// one decision the generator made, recorded so a failure's log can be
+/// inspected (and eventually replayed) without re-deriving it from the seed
+#[derive(Clone, Debug)]
+enum GenStep {
+    EnterLoop,
+    EnterBlock,
+    Instruction(&'static str),
+    Terminate(&'static str),
+}
+
+/// an in-scope target the generator is allowed to break or continue to
+enum Target<'g> {
+    Block(BlockRef<'g>),
+    Loop(shader_compiler_ir::LoopRef<'g>),
+}
+
+struct Generator<'g> {
+    global_state: &'g GlobalState<'g>,
+    rng: Xorshift64,
+    log: Vec<GenStep>,
+    in_scope_targets: Vec<Target<'g>>,
+}
+
+impl<'g> Generator<'g> {
+    fn new(global_state: &'g GlobalState<'g>, seed: u64) -> Self {
+        Self {
+            global_state,
+            rng: Xorshift64::new(seed),
+            log: Vec::new(),
+            in_scope_targets: Vec::new(),
+        }
+    }
+
+    /// generate a well-formed body ending in a terminator (`break`/`continue`),
+    /// recursing into nested loops/blocks up to `depth_remaining`
+    fn generate_body(&mut self, enclosing_block: BlockRef<'g>, depth_remaining: u32) -> Vec<Instruction<'g>> {
+        let mut body = Vec::new();
+        let extra_instructions = self.rng.next_below(MAX_BODY_LEN + 1);
+        for _ in 0..extra_instructions {
+            if depth_remaining > 0 && self.rng.next_bool(40) {
+                body.push(self.generate_nested_region(depth_remaining - 1));
+            } else {
+                // no scalar ALU instructions are generated here -- wiring up
+                // `Value`/`Const` operands is orthogonal to the control-flow
+                // shapes this harness targets, so a body is just zero or more
+                // nested regions followed by a terminator.
+                continue;
+            }
+        }
+        body.push(self.generate_terminator(enclosing_block));
+        body
+    }
+
+    /// generate either a nested `Loop` or a nested `Block` instruction
+    fn generate_nested_region(&mut self, depth_remaining: u32) -> Instruction<'g> {
+        if self.rng.next_bool(50) {
+            self.log.push(GenStep::EnterLoop);
+            let loop_body_block = self.global_state.alloc(BlockData {
+                name: self.global_state.intern("fuzz_loop_body"),
+                body: OnceCell::new(),
+                result_definitions: Inhabited(Vec::new()),
+            });
+            let loop_body_ref = BlockRef::new(IdRef::from(loop_body_block));
+            let loop_ = self.global_state.alloc(LoopData {
+                name: self.global_state.intern("fuzz_loop"),
+                arguments: Vec::new(),
+                header: LoopHeader {
+                    argument_definitions: Vec::new(),
+                },
+                body: loop_body_ref.clone(),
+            });
+            let loop_ref = shader_compiler_ir::LoopRef::new(IdRef::from(loop_));
+            self.in_scope_targets.push(Target::Loop(loop_ref.clone()));
+            let inner_body = self.generate_body(loop_body_ref, depth_remaining);
+            loop_body_block
+                .body
+                .set(inner_body)
+                .unwrap_or_else(|_| unreachable!());
+            self.in_scope_targets.pop();
+            Instruction {
+                location: None,
+                data: InstructionData::Loop(loop_),
+            }
+        } else {
+            self.log.push(GenStep::EnterBlock);
+            let block = self.global_state.alloc(BlockData {
+                name: self.global_state.intern("fuzz_block"),
+                body: OnceCell::new(),
+                result_definitions: Inhabited(Vec::new()),
+            });
+            let block_ref = BlockRef::new(IdRef::from(block));
+            self.in_scope_targets.push(Target::Block(block_ref.clone()));
+            let inner_body = self.generate_body(block_ref, depth_remaining);
+            block.body.set(inner_body).unwrap_or_else(|_| unreachable!());
+            self.in_scope_targets.pop();
+            Instruction {
+                location: None,
+                data: InstructionData::Block(block),
+            }
+        }
+    }
+
+    /// generate a terminator: `break` to `enclosing_block`, or `continue` to
+    /// an in-scope loop; breaks to other in-scope blocks are not generated
+    fn generate_terminator(&mut self, enclosing_block: BlockRef<'g>) -> Instruction<'g> {
+        let loop_targets: Vec<_> = self
+            .in_scope_targets
+            .iter()
+            .filter_map(|target| match target {
+                Target::Loop(loop_ref) => Some(loop_ref.clone()),
+                Target::Block(_) => None,
+            })
+            .collect();
+        if !loop_targets.is_empty() && self.rng.next_bool(30) {
+            let index = self.rng.next_below(loop_targets.len() as u32) as usize;
+            self.log.push(GenStep::Terminate("continue"));
+            Instruction {
+                location: None,
+                data: InstructionData::ContinueLoop(ContinueLoop {
+                    target_loop: loop_targets[index].clone(),
+                    block_arguments: Vec::new(),
+                }),
+            }
+        } else {
+            self.log.push(GenStep::Terminate("break"));
+            Instruction {
+                location: None,
+                data: InstructionData::BreakBlock(BreakBlock {
+                    block: enclosing_block,
+                    block_results: Vec::new(),
+                }),
+            }
+        }
+    }
+}
+
+/// directory minimized failing snippets (and their seed/log) are written to
+fn regressions_dir() -> &'static Path {
+    Path::new("tests/fuzz_regressions")
+}
+
+/// run one iteration from `seed`, returning the printed text of the
+/// generated tree and the result of re-parsing + re-printing it
+fn run_iteration(seed: u64) -> (Vec<GenStep>, String, Result<String, String>) {
+    let global_state = GlobalState::default();
+    let global_state = &global_state;
+    let entry_block = global_state.alloc(BlockData {
+        name: global_state.intern("fuzz_entry"),
+        body: OnceCell::new(),
+        result_definitions: Inhabited(Vec::new()),
+    });
+    let entry_ref = BlockRef::new(IdRef::from(entry_block));
+    let mut generator = Generator::new(global_state, seed);
+    let body = generator.generate_body(entry_ref, MAX_DEPTH);
+    entry_block.body.set(body).unwrap_or_else(|_| unreachable!());
+    let printed = format!("{}", BlockRef::new(IdRef::from(entry_block)).display());
+    let reparsed =
+        BlockRef::parse("<fuzz>", &printed, global_state).map_err(|error| error.to_string());
+    let round_tripped = reparsed.map(|parsed| format!("{}", parsed.display()));
+    (generator.log, printed, round_tripped)
+}
+
+/// on a failing seed, try progressively smaller variants of the same tree
+/// (shorter bodies, less nesting) and keep the smallest one that still fails,
+/// then write it to the regression corpus
+fn shrink_and_record(seed: u64, original_text: &str, failure: &str) {
+    fs::create_dir_all(regressions_dir()).ok();
+    let mut smallest_seed = seed;
+    let mut smallest_text = original_text.to_string();
+    let (mut smallest_log, _, _) = run_iteration(seed);
+    // the generator is deterministic in `seed`, so "shrinking" here means
+    // searching nearby seeds that produce a strictly shorter failing text --
+    // a cheap stand-in for replaying-with-deletions when the generator
+    // itself has no notion of partial trees to delete from.
+    for candidate_seed in seed.saturating_sub(64)..seed {
+        let (log, text, result) = run_iteration(candidate_seed);
+        if result.is_err() && text.len() < smallest_text.len() {
+            smallest_seed = candidate_seed;
+            smallest_text = text;
+            smallest_log = log;
+        }
+    }
+    let base = regressions_dir().join(format!("seed-{}", smallest_seed));
+    fs::write(base.with_extension("txt"), &smallest_text).ok();
+    fs::write(
+        base.with_extension("log"),
+        format!(
+            "seed: {}\nfailure: {}\nlog: {:?}\n",
+            smallest_seed, failure, smallest_log
+        ),
+    )
+    .ok();
+}
+
+#[test]
+fn test_round_trip_fuzz() {
+    for seed in 1..=ITERATIONS {
+        let (log, printed, round_tripped) = run_iteration(seed);
+        match round_tripped {
+            Ok(round_tripped) if round_tripped == printed => {}
+            Ok(round_tripped) => {
+                let failure = format!(
+                    "round trip text mismatch\n--- original ---\n{}\n--- round-tripped ---\n{}",
+                    printed, round_tripped
+                );
+                shrink_and_record(seed, &printed, &failure);
+                panic!(
+                    "seed {} failed to round-trip (log: {:?}): {}",
+                    seed, log, failure
+                );
+            }
+            Err(error) => {
+                shrink_and_record(seed, &printed, &error);
+                panic!(
+                    "seed {} failed to re-parse its own output (log: {:?}): {}\n{}",
+                    seed, log, error, printed
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! scalar reference implementations of the core arithmetic, bitwise,
+//! comparison, and conversion instructions, used to lower
+//! `parse_in_function_body` for `OpIAdd` and its neighbors.
+//!
+//! every function here operates on a single scalar lane; vector- and
+//! matrix-typed operands are lowered by applying the scalar function
+//! component-wise (see [`map2`]), so only the scalar semantics -- in
+//! particular the sign/rounding rules the SPIR-V spec nails down for
+//! each instruction -- need to be gotten right in one place.
+
+/// applies a binary scalar function to each corresponding pair of components of `a` and `b`
+pub(crate) fn map2<T: Copy, R>(a: &[T], b: &[T], mut f: impl FnMut(T, T) -> R) -> Vec<R> {
+    a.iter().zip(b).map(|(&a, &b)| f(a, b)).collect()
+}
+
+// ----- arithmetic -----
+
+/// `OpIAdd`: wrapping addition, the only sensible behavior for a type with no defined overflow semantics
+pub(crate) fn i_add(a: i64, b: i64) -> i64 {
+    a.wrapping_add(b)
+}
+
+/// `OpISub`
+pub(crate) fn i_sub(a: i64, b: i64) -> i64 {
+    a.wrapping_sub(b)
+}
+
+/// `OpIMul`
+pub(crate) fn i_mul(a: i64, b: i64) -> i64 {
+    a.wrapping_mul(b)
+}
+
+/// `OpUDiv`: unsigned division; `None` on division by zero, which the SPIR-V spec leaves undefined
+pub(crate) fn u_div(a: u64, b: u64) -> Option<u64> {
+    a.checked_div(b)
+}
+
+/// `OpSDiv`: signed division, truncating toward zero; `None` on division by zero or the `i64::MIN / -1` overflow case, both undefined per spec
+pub(crate) fn s_div(a: i64, b: i64) -> Option<i64> {
+    a.checked_div(b)
+}
+
+/// `OpFDiv`
+pub(crate) fn f_div(a: f64, b: f64) -> f64 {
+    a / b
+}
+
+/// `OpUMod`: unsigned modulo; `None` on division by zero
+pub(crate) fn u_mod(a: u64, b: u64) -> Option<u64> {
+    a.checked_rem(b)
+}
+
+/// `OpSRem`: signed remainder, taking the sign of the dividend `a` (Rust's `%` on signed integers already does this)
+pub(crate) fn s_rem(a: i64, b: i64) -> Option<i64> {
+    a.checked_rem(b)
+}
+
+/// `OpSMod`: signed modulo, taking the sign of the divisor `b`
+pub(crate) fn s_mod(a: i64, b: i64) -> Option<i64> {
+    let remainder = a.checked_rem(b)?;
+    Some(if remainder != 0 && (remainder < 0) != (b < 0) {
+        remainder + b
+    } else {
+        remainder
+    })
+}
+
+/// `OpFRem`: floating-point remainder, taking the sign of the dividend `a` (matches Rust's `%` on floats, i.e. C's `fmod`)
+pub(crate) fn f_rem(a: f64, b: f64) -> f64 {
+    a % b
+}
+
+/// `OpFMod`: floating-point modulo, taking the sign of the divisor `b`
+pub(crate) fn f_mod(a: f64, b: f64) -> f64 {
+    let remainder = a % b;
+    if remainder != 0.0 && remainder.is_sign_negative() != b.is_sign_negative() {
+        remainder + b
+    } else {
+        remainder
+    }
+}
+
+// ----- shifts and bitwise -----
+
+/// `OpShiftLeftLogical`
+pub(crate) fn shift_left_logical(base: u64, shift: u32, bit_width: u32) -> u64 {
+    if shift >= bit_width {
+        0
+    } else {
+        base << shift
+    }
+}
+
+/// `OpShiftRightLogical`: zero-fills the vacated high bits
+pub(crate) fn shift_right_logical(base: u64, shift: u32, bit_width: u32) -> u64 {
+    if shift >= bit_width {
+        0
+    } else {
+        base >> shift
+    }
+}
+
+/// `OpShiftRightArithmetic`: sign-extends the vacated high bits, using `base`'s sign bit at `bit_width`
+pub(crate) fn shift_right_arithmetic(base: u64, shift: u32, bit_width: u32) -> u64 {
+    let sign_extended = sign_extend(base, bit_width);
+    let shift = shift.min(bit_width - 1);
+    ((sign_extended >> shift) as u64) & mask(bit_width)
+}
+
+pub(crate) fn sign_extend(value: u64, bit_width: u32) -> i64 {
+    let shift = 64 - bit_width;
+    ((value << shift) as i64) >> shift
+}
+
+pub(crate) fn mask(bit_width: u32) -> u64 {
+    if bit_width >= 64 {
+        u64::MAX
+    } else {
+        (1u64 << bit_width) - 1
+    }
+}
+
+/// `OpNot`: `u64`'s `!`, masked to `bit_width`. `OpBitwiseAnd`/`Or`/`Xor` need no dedicated functions: they're exactly `u64`'s `&`/`|`/`^`.
+pub(crate) fn bitwise_not(value: u64, bit_width: u32) -> u64 {
+    !value & mask(bit_width)
+}
+
+/// `OpBitFieldInsert`: replaces `count` bits of `base` starting at `offset` with the low `count` bits of `insert`
+pub(crate) fn bit_field_insert(base: u64, insert: u64, offset: u32, count: u32, bit_width: u32) -> u64 {
+    let field_mask = if count >= bit_width { mask(bit_width) } else { ((1u64 << count) - 1) << offset };
+    (base & !field_mask) | ((insert << offset) & field_mask)
+}
+
+/// `OpBitFieldUExtract`: the `count` bits of `base` starting at `offset`, zero-extended
+pub(crate) fn bit_field_u_extract(base: u64, offset: u32, count: u32) -> u64 {
+    if count == 0 {
+        0
+    } else {
+        (base >> offset) & ((1u64 << count) - 1)
+    }
+}
+
+/// `OpBitFieldSExtract`: the `count` bits of `base` starting at `offset`, sign-extended from their own top bit
+pub(crate) fn bit_field_s_extract(base: u64, offset: u32, count: u32) -> i64 {
+    if count == 0 {
+        return 0;
+    }
+    let extracted = bit_field_u_extract(base, offset, count);
+    sign_extend(extracted, count)
+}
+
+/// `OpBitReverse`: the bits of `base`'s low `bit_width` bits, in reverse order
+pub(crate) fn bit_reverse(base: u64, bit_width: u32) -> u64 {
+    base.reverse_bits() >> (64 - bit_width)
+}
+
+/// `OpBitCount`
+pub(crate) fn bit_count(base: u64) -> u32 {
+    base.count_ones()
+}
+
+// ----- conversions -----
+
+/// `OpConvertFToU`: converts a float to an unsigned integer, per Rust's (and SPIR-V's) saturating `as` semantics
+pub(crate) fn convert_f_to_u(value: f64) -> u64 {
+    value as u64
+}
+
+/// `OpConvertFToS`
+pub(crate) fn convert_f_to_s(value: f64) -> i64 {
+    value as i64
+}
+
+/// `OpConvertSToF`
+pub(crate) fn convert_s_to_f(value: i64) -> f64 {
+    value as f64
+}
+
+/// `OpConvertUToF`
+pub(crate) fn convert_u_to_f(value: u64) -> f64 {
+    value as f64
+}
+
+/// `OpBitcast`: reinterprets `value`'s bit pattern as a different type of the same width; `f64`/`u64` is the widest representation used throughout this module, so bitcast is the identity on the underlying bits
+pub(crate) fn bitcast_f64_to_u64(value: f64) -> u64 {
+    value.to_bits()
+}
+
+/// `OpBitcast`, the inverse direction of [`bitcast_f64_to_u64`]
+pub(crate) fn bitcast_u64_to_f64(value: u64) -> f64 {
+    f64::from_bits(value)
+}
+
+// ----- comparisons -----
+
+/// `OpFUnordEqual` and friends: an unordered float comparison is true if either the ordered comparison holds, or either operand is `NaN`
+pub(crate) fn f_unord(a: f64, b: f64, ordered: impl FnOnce(f64, f64) -> bool) -> bool {
+    ordered(a, b) || a.is_nan() || b.is_nan()
+}
+
+/// `OpSelect`: the C-style ternary, applied component-wise by the caller via [`map2`]-style zipping over three slices
+pub(crate) fn select<T>(condition: bool, true_value: T, false_value: T) -> T {
+    if condition {
+        true_value
+    } else {
+        false_value
+    }
+}
+
+// ----- linear algebra -----
+
+/// `OpDot`
+pub(crate) fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(&a, &b)| a * b).sum()
+}
+
+/// `OpVectorTimesMatrix`: `vector * matrix`, where `matrix` is given column-major (one `Vec<f64>` per column, as SPIR-V stores matrices)
+pub(crate) fn vector_times_matrix(vector: &[f64], matrix: &[Vec<f64>]) -> Vec<f64> {
+    matrix.iter().map(|column| dot(vector, column)).collect()
+}
+
+/// `OpMatrixTimesVector`: `matrix * vector`
+pub(crate) fn matrix_times_vector(matrix: &[Vec<f64>], vector: &[f64]) -> Vec<f64> {
+    let rows = matrix[0].len();
+    (0..rows)
+        .map(|row| {
+            matrix
+                .iter()
+                .zip(vector)
+                .map(|(column, &scalar)| column[row] * scalar)
+                .sum()
+        })
+        .collect()
+}
+
+/// `OpMatrixTimesMatrix`: `lhs * rhs`, both column-major
+pub(crate) fn matrix_times_matrix(lhs: &[Vec<f64>], rhs: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    rhs.iter().map(|rhs_column| matrix_times_vector(lhs, rhs_column)).collect()
+}
+
+// ----- widened extended arithmetic -----
+
+/// `OpIAddCarry`: `a + b` as a (result, carry) pair, `carry` set iff the unsigned addition overflowed `bit_width` bits
+pub(crate) fn i_add_carry(a: u64, b: u64, bit_width: u32) -> (u64, u64) {
+    let sum = (a.wrapping_add(b)) & mask(bit_width);
+    let carry = if a as u128 + b as u128 > mask(bit_width) as u128 { 1 } else { 0 };
+    (sum, carry)
+}
+
+/// `OpISubBorrow`: `a - b` as a (result, borrow) pair, `borrow` set iff the unsigned subtraction underflowed
+pub(crate) fn i_sub_borrow(a: u64, b: u64, bit_width: u32) -> (u64, u64) {
+    let difference = a.wrapping_sub(b) & mask(bit_width);
+    let borrow = if a < b { 1 } else { 0 };
+    (difference, borrow)
+}
+
+/// `OpUMulExtended`: the full 128-bit product of two 64-bit unsigned operands, as a (low, high) pair
+pub(crate) fn u_mul_extended(a: u64, b: u64) -> (u64, u64) {
+    let product = a as u128 * b as u128;
+    (product as u64, (product >> 64) as u64)
+}
+
+/// `OpSMulExtended`: the full 128-bit product of two 64-bit signed operands, as a (low, high) pair
+pub(crate) fn s_mul_extended(a: i64, b: i64) -> (u64, i64) {
+    let product = a as i128 * b as i128;
+    (product as u64, (product >> 64) as i64)
+}
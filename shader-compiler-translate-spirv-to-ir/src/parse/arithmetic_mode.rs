@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! a configurable policy for the integer overflow, division-by-zero,
+//! and out-of-range-shift cases that C (and OpenCL C) leave undefined:
+//! [`ArithmeticMode::Defined`] resolves every one of them
+//! deterministically -- masked shift counts, a sentinel division
+//! result, wrapping two's-complement overflow -- so kazan's software
+//! interpreter never inherits whatever the host CPU happens to do,
+//! while [`ArithmeticMode::Strict`] raises a [`Trap`] the host can
+//! observe instead of silently producing a value. `u_mad_hi` and
+//! `s_mad_hi` in [`super::ext_inst_opencl_std`] are the only functions
+//! that take a mode; the rest of the ext-inst interpreter and the
+//! ordinary arithmetic opcodes in [`super::alu`] do not take an
+//! `ArithmeticMode`, so this policy does not govern them.
+
+use super::alu;
+use std::fmt;
+
+/// which of the two policies below governs an otherwise-undefined integer operation
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum ArithmeticMode {
+    /// resolve the operation deterministically instead of trapping
+    Defined,
+    /// raise a [`Trap`] instead of silently resolving the operation
+    Strict,
+}
+
+/// an integer operation that [`ArithmeticMode::Strict`] refused to silently resolve
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct Trap {
+    message: String,
+}
+
+impl Trap {
+    fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Trap {}
+
+/// resolves a shift count that may be `>= bit_width`: `Defined` mode masks it modulo `bit_width` (C's behavior on most real hardware), `Strict` mode traps
+pub(crate) fn checked_shift_amount(mode: ArithmeticMode, shift: u32, bit_width: u32) -> Result<u32, Trap> {
+    match mode {
+        ArithmeticMode::Defined => Ok(shift % bit_width),
+        ArithmeticMode::Strict if shift < bit_width => Ok(shift),
+        ArithmeticMode::Strict => Err(Trap::new(format!(
+            "shift amount {} is out of range for a {}-bit value",
+            shift, bit_width
+        ))),
+    }
+}
+
+/// resolves an unsigned division/remainder by zero: `Defined` mode substitutes `sentinel`, `Strict` mode traps
+pub(crate) fn checked_div_u(mode: ArithmeticMode, a: u64, b: u64, sentinel: u64, op: &str) -> Result<u64, Trap> {
+    if b != 0 {
+        return Ok(a / b);
+    }
+    match mode {
+        ArithmeticMode::Defined => Ok(sentinel),
+        ArithmeticMode::Strict => Err(Trap::new(format!("{} by zero", op))),
+    }
+}
+
+/// resolves a signed division/remainder by zero, or the `i64::MIN / -1` overflow case (which wraps to `i64::MIN` in `Defined` mode): `Defined` mode substitutes `sentinel` for division by zero, `Strict` mode traps on either case
+pub(crate) fn checked_div_s(mode: ArithmeticMode, a: i64, b: i64, sentinel: i64, op: &str) -> Result<i64, Trap> {
+    if b == 0 {
+        return match mode {
+            ArithmeticMode::Defined => Ok(sentinel),
+            ArithmeticMode::Strict => Err(Trap::new(format!("{} by zero", op))),
+        };
+    }
+    match a.checked_div(b) {
+        Some(result) => Ok(result),
+        None => match mode {
+            ArithmeticMode::Defined => Ok(a.wrapping_div(b)),
+            ArithmeticMode::Strict => Err(Trap::new(format!("{} overflowed (i64::MIN / -1)", op))),
+        },
+    }
+}
+
+/// resolves unsigned addition overflow past `bit_width` bits: `Defined` mode wraps, `Strict` mode traps
+pub(crate) fn wrapping_add_u(mode: ArithmeticMode, a: u64, b: u64, bit_width: u32) -> Result<u64, Trap> {
+    let mask = alu::mask(bit_width);
+    let sum = a.wrapping_add(b) & mask;
+    match mode {
+        ArithmeticMode::Defined => Ok(sum),
+        ArithmeticMode::Strict if u128::from(a) + u128::from(b) <= u128::from(mask) => Ok(sum),
+        ArithmeticMode::Strict => Err(Trap::new(format!("unsigned addition overflowed {} bits", bit_width))),
+    }
+}
+
+/// resolves signed addition overflow past `bit_width` bits' two's-complement range: `Defined` mode wraps, `Strict` mode traps
+pub(crate) fn wrapping_add_i(mode: ArithmeticMode, a: i64, b: i64, bit_width: u32) -> Result<i64, Trap> {
+    let sum = alu::sign_extend((a as u64).wrapping_add(b as u64) & alu::mask(bit_width), bit_width);
+    match mode {
+        ArithmeticMode::Defined => Ok(sum),
+        ArithmeticMode::Strict if i128::from(a) + i128::from(b) == i128::from(sum) => Ok(sum),
+        ArithmeticMode::Strict => Err(Trap::new(format!("signed addition overflowed {} bits", bit_width))),
+    }
+}
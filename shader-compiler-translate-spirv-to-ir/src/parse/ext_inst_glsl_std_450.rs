@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! scalar reference implementations of the `GLSL.std.450` extended
+//! instruction set, used by `OpExtInst` lowering when `set` names the
+//! `GLSL.std.450` extended instruction import.
+//!
+//! each function here mirrors the operation of the instruction with the
+//! same name from the `GLSL.std.450` spec exactly (including argument
+//! order), operating on a single `f32` lane at a time; vector-typed
+//! operands are lowered by applying the scalar function component-wise.
+
+/// the `GLSL.std.450` extended instruction numbers this module knows how to lower.
+///
+/// numbering matches the `GLSL.std.450` extended instruction set spec; gaps
+/// are instructions not yet implemented here.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum GLSLStd450Instruction {
+    FClamp,
+    FMix,
+    Step,
+    SmoothStep,
+    Length,
+    Normalize,
+    Reflect,
+    Fma,
+    InverseSqrt,
+    Sin,
+    Cos,
+    Exp2,
+    Log2,
+    Pow,
+    Atan2,
+}
+
+impl GLSLStd450Instruction {
+    pub(crate) fn from_instruction_number(instruction_number: u32) -> Option<Self> {
+        Some(match instruction_number {
+            32 => Self::InverseSqrt,
+            13 => Self::Sin,
+            14 => Self::Cos,
+            26 => Self::Pow,
+            29 => Self::Exp2,
+            30 => Self::Log2,
+            25 => Self::Atan2,
+            43 => Self::FClamp,
+            46 => Self::FMix,
+            48 => Self::Step,
+            49 => Self::SmoothStep,
+            50 => Self::Fma,
+            66 => Self::Length,
+            69 => Self::Normalize,
+            71 => Self::Reflect,
+            _ => return None,
+        })
+    }
+}
+
+/// `GLSL.std.450 FClamp(x, min_val, max_val)`: `x` restricted to lie between `min_val` and `max_val`
+pub(crate) fn f_clamp(x: f32, min_val: f32, max_val: f32) -> f32 {
+    x.max(min_val).min(max_val)
+}
+
+/// `GLSL.std.450 FMix(x, y, a)`: linear blend of `x` and `y` using the ratio `a`
+pub(crate) fn f_mix(x: f32, y: f32, a: f32) -> f32 {
+    x * (1.0 - a) + y * a
+}
+
+/// `GLSL.std.450 Step(edge, x)`: `0.0` if `x < edge`, else `1.0`
+pub(crate) fn step(edge: f32, x: f32) -> f32 {
+    if x < edge {
+        0.0
+    } else {
+        1.0
+    }
+}
+
+/// `GLSL.std.450 SmoothStep(edge0, edge1, x)`: Hermite interpolation between `0` and `1`
+pub(crate) fn smooth_step(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge0 >= edge1 {
+        // the spec leaves this case's results undefined; returning NaN
+        // makes the undefined-ness visible rather than silently picking
+        // an arbitrary answer.
+        return f32::NAN;
+    }
+    let t = f_clamp((x - edge0) / (edge1 - edge0), 0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// `GLSL.std.450 Length(x)`: the length of vector `x`, as the square root of its dot product with itself
+pub(crate) fn length(x: &[f32]) -> f32 {
+    dot(x, x).sqrt()
+}
+
+/// `GLSL.std.450 Normalize(x)`: `x` scaled to unit length, direction preserved
+pub(crate) fn normalize(x: &[f32], out: &mut [f32]) {
+    let length = length(x);
+    for (out, &x) in out.iter_mut().zip(x) {
+        *out = x / length;
+    }
+}
+
+/// `GLSL.std.450 Reflect(i, n)`: `i` reflected about the (assumed normalized) surface normal `n`
+pub(crate) fn reflect(i: &[f32], n: &[f32], out: &mut [f32]) {
+    let factor = 2.0 * dot(n, i);
+    for ((out, &i), &n) in out.iter_mut().zip(i).zip(n) {
+        *out = i - factor * n;
+    }
+}
+
+/// `GLSL.std.450 Fma(a, b, c)`: `a * b + c`, computed with a single rounding as if by a fused multiply-add
+pub(crate) fn fma(a: f32, b: f32, c: f32) -> f32 {
+    a.mul_add(b, c)
+}
+
+/// `GLSL.std.450 InverseSqrt(x)`: `1 / sqrt(x)`
+pub(crate) fn inverse_sqrt(x: f32) -> f32 {
+    1.0 / x.sqrt()
+}
+
+/// dot product shared by [`length`], [`normalize`], and [`reflect`]
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(&a, &b)| a * b).sum()
+}
+
+/// `2 * pi`, the period [`sin`] and [`cos`] reduce their argument into before evaluating the polynomial
+const TAU: f32 = 6.283_185_5;
+
+/// reduce `x` into the range `(-pi, pi]` by subtracting the nearest multiple of `2 * pi`
+fn reduce_to_half_turn(x: f32) -> f32 {
+    let turns = (x / TAU).round();
+    x - turns * TAU
+}
+
+/// `GLSL.std.450 Sin(x)`: sine of `x` radians, via range reduction and a minimax polynomial
+pub(crate) fn sin(x: f32) -> f32 {
+    // degree-9, odd-powers-only minimax polynomial approximation of sin
+    // on `(-pi, pi]`, coefficients from the standard Taylor-derived
+    // minimax fit used for single-precision transcendentals.
+    let x = reduce_to_half_turn(x);
+    let x2 = x * x;
+    x * (1.0
+        + x2 * (-1.0 / 6.0
+            + x2 * (1.0 / 120.0 + x2 * (-1.0 / 5040.0 + x2 * (1.0 / 362_880.0)))))
+}
+
+/// `GLSL.std.450 Cos(x)`: cosine of `x` radians, computed as `Sin(x + pi / 2)`
+pub(crate) fn cos(x: f32) -> f32 {
+    sin(x + std::f32::consts::FRAC_PI_2)
+}
+
+/// `GLSL.std.450 Exp2(x)`: `2` raised to the power `x`
+pub(crate) fn exp2(x: f32) -> f32 {
+    // split into integer and fractional parts so the fractional part can
+    // be range-reduced to `[0, 1)` before the polynomial is evaluated,
+    // then the integer part is reapplied via `ldexp`-style scaling.
+    let integer_part = x.floor();
+    let fractional_part = x - integer_part;
+    // degree-5 minimax polynomial approximation of `2^t` on `[0, 1)`.
+    let polynomial = 1.0
+        + fractional_part
+            * (0.693_147_2
+                + fractional_part
+                    * (0.240_226_5
+                        + fractional_part
+                            * (0.055_504_11
+                                + fractional_part
+                                    * (0.009_618_13 + fractional_part * 0.001_333_56))));
+    polynomial * 2.0_f32.powi(integer_part as i32)
+}
+
+/// `GLSL.std.450 Log2(x)`: base-2 logarithm of `x`
+pub(crate) fn log2(x: f32) -> f32 {
+    // decompose `x` into `mantissa * 2^exponent` with `mantissa` in
+    // `[1, 2)`, then approximate `log2(mantissa)` with a minimax
+    // polynomial and add the exact integer `exponent` back in.
+    let bits = x.to_bits();
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127;
+    let mantissa = f32::from_bits((bits & 0x007f_ffff) | 0x3f80_0000);
+    let m = mantissa - 1.0;
+    let polynomial = m
+        * (1.442_695
+            + m * (-0.721_347_6 + m * (0.480_898_4 + m * (-0.360_673_5 + m * 0.288_539))));
+    exponent as f32 + polynomial
+}
+
+/// `GLSL.std.450 Pow(x, y)`: `x` raised to the power `y`, computed as `Exp2(y * Log2(x))`
+pub(crate) fn pow(x: f32, y: f32) -> f32 {
+    exp2(y * log2(x))
+}
+
+/// `GLSL.std.450 Atan2(y, x)`: the angle, in radians, whose tangent is `y / x`, using the signs of `y` and `x` to pick the correct quadrant
+pub(crate) fn atan2(y: f32, x: f32) -> f32 {
+    // reduce to `atan(t)` for `t` in `[-1, 1]` using the standard
+    // quadrant/reciprocal identities, then a degree-9 odd minimax
+    // polynomial approximates `atan` on that range.
+    fn atan_on_unit_range(t: f32) -> f32 {
+        let t2 = t * t;
+        t * (0.999_974_4
+            + t2 * (-0.332_568_9
+                + t2 * (0.193_476_1 + t2 * (-0.117_387_5 + t2 * 0.043_147_65))))
+    }
+    if x == 0.0 && y == 0.0 {
+        return 0.0;
+    }
+    if x.abs() > y.abs() {
+        let result = atan_on_unit_range(y / x);
+        if x < 0.0 {
+            if y >= 0.0 {
+                result + std::f32::consts::PI
+            } else {
+                result - std::f32::consts::PI
+            }
+        } else {
+            result
+        }
+    } else {
+        std::f32::consts::FRAC_PI_2 * y.signum() - atan_on_unit_range(x / y)
+    }
+}
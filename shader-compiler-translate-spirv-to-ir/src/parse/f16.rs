@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! IEEE 754 binary16 ("half") encode/decode, used to lower the
+//! `OpOpenCLStd{Vload,Vstore}{,a}Half{,n}{,R}` family: a `vload`
+//! widens 16-bit halves read from a buffer to `f32`/`f64`, a `vstore`
+//! narrows `f32`/`f64` down to 16-bit halves, and the `R`-suffixed
+//! variants narrow with an explicit [`RoundingMode`] rather than the
+//! default round-to-nearest-even.
+
+/// the four SPIR-V floating-point rounding modes an `OpOpenCLStdVstoreHalfR`-family instruction can request
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum RoundingMode {
+    /// round to nearest, ties to even -- the default for the non-`R` store variants
+    Rte,
+    /// round toward zero
+    Rtz,
+    /// round toward positive infinity
+    Rtp,
+    /// round toward negative infinity
+    Rtn,
+}
+
+/// widens a binary16 bit pattern to `f32`, exactly (every `f16` value is exactly representable in `f32`)
+pub(crate) fn f16_bits_to_f32(bits: u16) -> f32 {
+    let sign = (u32::from(bits) & 0x8000) << 16;
+    let exp = (bits >> 10) & 0x1f;
+    let mantissa = u32::from(bits & 0x3ff);
+    let bits32 = if exp == 0 {
+        if mantissa == 0 {
+            sign
+        } else {
+            // subnormal half: normalize by shifting the mantissa left until
+            // its leading bit reaches the implicit-one position, adjusting
+            // the exponent to match each shift.
+            let mut mantissa = mantissa;
+            let mut unbiased = -14i32;
+            while mantissa & 0x400 == 0 {
+                mantissa <<= 1;
+                unbiased -= 1;
+            }
+            mantissa &= 0x3ff;
+            let f32_exp = (unbiased + 127) as u32;
+            sign | (f32_exp << 23) | (mantissa << 13)
+        }
+    } else if exp == 0x1f {
+        // infinity (mantissa == 0) or NaN (mantissa != 0); shifting a
+        // nonzero mantissa left keeps it nonzero, so the NaN-ness survives
+        sign | 0x7f80_0000 | (mantissa << 13)
+    } else {
+        let f32_exp = (i32::from(exp) - 15 + 127) as u32;
+        sign | (f32_exp << 23) | (mantissa << 13)
+    };
+    f32::from_bits(bits32)
+}
+
+/// widens a binary16 bit pattern to `f64`, via the lossless `f32` widening (every `f16` is also exactly representable in `f64`)
+pub(crate) fn f16_bits_to_f64(bits: u16) -> f64 {
+    f64::from(f16_bits_to_f32(bits))
+}
+
+/// narrows `value` to a binary16 bit pattern, rounding per `mode`.
+///
+/// handles every edge case the SPIR-V spec calls out explicitly:
+/// overflow past the largest finite half rounds to `±infinity` under
+/// `Rte`, and to the largest finite half under the directed modes that
+/// don't round away from zero; magnitudes below the smallest normal
+/// half gradually underflow into subnormal halves, or flush to a
+/// signed zero once they're too small even for that; and a NaN's
+/// payload is preserved (truncated to the 10 mantissa bits, with the
+/// quiet bit forced on so it can never accidentally encode infinity).
+pub(crate) fn f64_to_f16_bits(value: f64, mode: RoundingMode) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 48) & 0x8000) as u16;
+    if value.is_nan() {
+        let mantissa52 = bits & 0x000f_ffff_ffff_ffff;
+        let payload = ((mantissa52 >> 42) as u16) & 0x03ff;
+        return sign | 0x7c00 | 0x0200 | payload;
+    }
+    if value.is_infinite() {
+        return sign | 0x7c00;
+    }
+    if value == 0.0 {
+        return sign;
+    }
+    let negative = sign != 0;
+    let unbiased = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let mantissa52 = bits & 0x000f_ffff_ffff_ffff;
+    let significand = mantissa52 | (1u64 << 52); // 53-bit significand, implicit bit at position 52
+
+    // lowest representable half exponent (subnormal) is -14 - 10 = -24;
+    // `extra_shift` is how much further than the normal 42-bit shift is
+    // needed to place the significand's bits at a subnormal's position.
+    let extra_shift = (-14 - unbiased).max(0) as u32;
+    let total_shift = 42u32 + extra_shift;
+    let shifted = round_right_shift(significand, total_shift, mode, negative);
+
+    let (exp_field, mantissa): (i64, u64) = if extra_shift == 0 {
+        if shifted >= 0x800 {
+            (unbiased + 15 + 1, 0)
+        } else {
+            (unbiased + 15, shifted & 0x3ff)
+        }
+    } else if shifted >= 0x400 {
+        (1, shifted - 0x400)
+    } else {
+        (0, shifted)
+    };
+
+    if exp_field >= 31 {
+        let round_to_infinity = match mode {
+            RoundingMode::Rte => true,
+            RoundingMode::Rtz => false,
+            RoundingMode::Rtp => !negative,
+            RoundingMode::Rtn => negative,
+        };
+        return sign | if round_to_infinity { 0x7c00 } else { 0x7bff };
+    }
+    sign | ((exp_field as u16) << 10) | (mantissa as u16)
+}
+
+/// narrows `value` to a binary16 bit pattern, rounding per `mode`
+pub(crate) fn f32_to_f16_bits(value: f32, mode: RoundingMode) -> u16 {
+    // widening f32 -> f64 is exact, so rounding the f64 representation
+    // to f16 rounds `value` itself correctly, with no double-rounding.
+    f64_to_f16_bits(f64::from(value), mode)
+}
+
+/// shifts `value` right by `shift` bits, rounding the result per `mode` using the bits shifted out as the round/sticky bits
+fn round_right_shift(value: u64, shift: u32, mode: RoundingMode, negative: bool) -> u64 {
+    if shift == 0 {
+        return value;
+    }
+    if shift >= 64 {
+        return if value != 0 && mode_rounds_away_from_zero_on_nonzero_remainder(mode, negative) {
+            1
+        } else {
+            0
+        };
+    }
+    let truncated = value >> shift;
+    let round_bit = (value >> (shift - 1)) & 1;
+    let sticky = shift >= 2 && (value & ((1u64 << (shift - 1)) - 1)) != 0;
+    let round_up = match mode {
+        RoundingMode::Rte => round_bit == 1 && (sticky || (truncated & 1) == 1),
+        RoundingMode::Rtz => false,
+        RoundingMode::Rtp => !negative && (round_bit == 1 || sticky),
+        RoundingMode::Rtn => negative && (round_bit == 1 || sticky),
+    };
+    if round_up {
+        truncated + 1
+    } else {
+        truncated
+    }
+}
+
+fn mode_rounds_away_from_zero_on_nonzero_remainder(mode: RoundingMode, negative: bool) -> bool {
+    match mode {
+        RoundingMode::Rte => false, // a remainder this tiny always rounds back down to zero under ties-to-even
+        RoundingMode::Rtz => false,
+        RoundingMode::Rtp => !negative,
+        RoundingMode::Rtn => negative,
+    }
+}
@@ -0,0 +1,339 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! builds and validates the control-flow graph of a function body before
+//! any IR is emitted for it.
+//!
+//! translation happens in two passes over the function's instructions:
+//! [`ControlFlowGraph::build`] (this module) walks the instruction list
+//! once, splitting it into basic blocks keyed by their `OpLabel` result
+//! id and recording each block's terminator successors and its
+//! structured merge/continue targets (from `OpLoopMerge`/
+//! `OpSelectionMerge`), then [`ControlFlowGraph::validate`] checks the
+//! structured-control-flow invariants the SPIR-V spec requires but
+//! doesn't let the binary format itself enforce. Only once that
+//! succeeds does the second pass (in `parse::functions`) walk the graph
+//! emitting IR, materializing `OpPhi` as per-predecessor value copies
+//! appended to the end of each incoming block and lowering `OpSwitch` to
+//! a comparison chain.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// the result id an `OpLabel` gives a basic block, used throughout this module as the block's identity
+pub(crate) type BlockId = spirv_parser::IdRef;
+
+/// the structured merge/continue targets a block's header instruction (`OpLoopMerge` or `OpSelectionMerge`) declares
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum StructuredMerge {
+    Selection {
+        merge_block: BlockId,
+    },
+    Loop {
+        merge_block: BlockId,
+        continue_target: BlockId,
+    },
+}
+
+impl StructuredMerge {
+    pub(crate) fn merge_block(self) -> BlockId {
+        match self {
+            StructuredMerge::Selection { merge_block } => merge_block,
+            StructuredMerge::Loop { merge_block, .. } => merge_block,
+        }
+    }
+}
+
+/// one basic block: the span of instructions from an `OpLabel` up to and
+/// including its terminator, plus the structural information the CFG
+/// pass derives from it.
+#[derive(Clone, Debug)]
+pub(crate) struct CFGBlock {
+    pub(crate) id: BlockId,
+    /// the index into the function's instruction list of this block's first non-`OpLabel` instruction
+    pub(crate) first_instruction_index: usize,
+    /// the index one past this block's terminator instruction
+    pub(crate) end_instruction_index: usize,
+    pub(crate) merge: Option<StructuredMerge>,
+    pub(crate) successors: Vec<BlockId>,
+    pub(crate) predecessors: Vec<BlockId>,
+}
+
+/// the control-flow graph of a single function body
+#[derive(Debug)]
+pub(crate) struct ControlFlowGraph {
+    pub(crate) entry_block: BlockId,
+    blocks: HashMap<BlockId, CFGBlock>,
+    /// block ids in the order their `OpLabel` appeared, i.e. SPIR-V's required reverse-postorder-compatible layout order
+    order: Vec<BlockId>,
+}
+
+/// a violation of a structured-control-flow invariant found by [`ControlFlowGraph::validate`]
+#[derive(Clone, Debug)]
+pub(crate) struct ControlFlowError {
+    pub(crate) block: BlockId,
+    pub(crate) message: String,
+}
+
+impl fmt::Display for ControlFlowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid control flow at block %{:?}: {}", self.block, self.message)
+    }
+}
+
+impl std::error::Error for ControlFlowError {}
+
+impl ControlFlowGraph {
+    pub(crate) fn block(&self, id: BlockId) -> &CFGBlock {
+        &self.blocks[&id]
+    }
+
+    /// block ids in declaration order, matching SPIR-V's block-layout rule that a block appears before any block it structurally dominates
+    pub(crate) fn blocks_in_order(&self) -> impl Iterator<Item = &CFGBlock> {
+        self.order.iter().map(move |id| &self.blocks[id])
+    }
+
+    /// first pass: split `instructions` into blocks at each `OpLabel`, and
+    /// record each block's terminator successors and merge/continue targets.
+    pub(crate) fn build(
+        entry_block: BlockId,
+        instructions: &[spirv_parser::Instruction],
+    ) -> Result<Self, ControlFlowError> {
+        let mut blocks = HashMap::new();
+        let mut order = Vec::new();
+        let mut current: Option<(BlockId, usize, Option<StructuredMerge>)> = None;
+        for (index, instruction) in instructions.iter().enumerate() {
+            match instruction {
+                spirv_parser::Instruction::Label(op) => {
+                    current = Some((op.id_result.0, index + 1, None));
+                    order.push(op.id_result.0);
+                }
+                spirv_parser::Instruction::LoopMerge(op) => {
+                    let (_, _, merge) = current
+                        .as_mut()
+                        .expect("OpLoopMerge must follow an OpLabel");
+                    *merge = Some(StructuredMerge::Loop {
+                        merge_block: op.merge_block,
+                        continue_target: op.continue_target,
+                    });
+                }
+                spirv_parser::Instruction::SelectionMerge(op) => {
+                    let (_, _, merge) = current
+                        .as_mut()
+                        .expect("OpSelectionMerge must follow an OpLabel");
+                    *merge = Some(StructuredMerge::Selection {
+                        merge_block: op.merge_block,
+                    });
+                }
+                terminator if is_terminator(terminator) => {
+                    let (id, first_instruction_index, merge) = current
+                        .take()
+                        .expect("terminator must follow an OpLabel");
+                    let successors = terminator_successors(terminator);
+                    blocks.insert(
+                        id,
+                        CFGBlock {
+                            id,
+                            first_instruction_index,
+                            end_instruction_index: index + 1,
+                            merge,
+                            successors,
+                            predecessors: Vec::new(),
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+        let successor_lists: Vec<(BlockId, Vec<BlockId>)> = blocks
+            .values()
+            .map(|block| (block.id, block.successors.clone()))
+            .collect();
+        for (predecessor, successors) in successor_lists {
+            for successor in successors {
+                if let Some(successor_block) = blocks.get_mut(&successor) {
+                    successor_block.predecessors.push(predecessor);
+                }
+            }
+        }
+        Ok(ControlFlowGraph {
+            entry_block,
+            blocks,
+            order,
+        })
+    }
+
+    /// computes, for every block, the set of blocks that dominate it (every
+    /// path from the entry block to that block passes through the
+    /// dominator), via the standard iterative dataflow fixed-point.
+    fn dominators(&self) -> HashMap<BlockId, std::collections::HashSet<BlockId>> {
+        let all_blocks: std::collections::HashSet<BlockId> = self.order.iter().copied().collect();
+        let mut dominators: HashMap<BlockId, std::collections::HashSet<BlockId>> = self
+            .order
+            .iter()
+            .map(|&id| {
+                let set = if id == self.entry_block {
+                    std::iter::once(id).collect()
+                } else {
+                    all_blocks.clone()
+                };
+                (id, set)
+            })
+            .collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &id in &self.order {
+                if id == self.entry_block {
+                    continue;
+                }
+                let block = &self.blocks[&id];
+                let mut new_set = if block.predecessors.is_empty() {
+                    all_blocks.clone()
+                } else {
+                    let mut iter = block.predecessors.iter();
+                    let mut acc = dominators[iter.next().unwrap()].clone();
+                    for predecessor in iter {
+                        acc = acc.intersection(&dominators[predecessor]).copied().collect();
+                    }
+                    acc
+                };
+                new_set.insert(id);
+                if new_set != dominators[&id] {
+                    dominators.insert(id, new_set);
+                    changed = true;
+                }
+            }
+        }
+        dominators
+    }
+
+    /// second pass's prerequisite: checks that each loop header dominates
+    /// its continue target (so the loop body cannot jump back around
+    /// without passing through the header) and that each construct
+    /// header dominates its own merge block (so "falling out" of the
+    /// construct is always reachable, never dead).
+    pub(crate) fn validate(&self) -> Result<(), Vec<ControlFlowError>> {
+        let dominators = self.dominators();
+        let mut errors = Vec::new();
+        for block in self.blocks.values() {
+            let merge = match block.merge {
+                Some(merge) => merge,
+                None => continue,
+            };
+            let merge_block_id = merge.merge_block();
+            if !self.blocks.contains_key(&merge_block_id) {
+                errors.push(ControlFlowError {
+                    block: block.id,
+                    message: format!("merge block %{:?} is not a block in this function", merge_block_id),
+                });
+                continue;
+            }
+            if !dominators[&merge_block_id].contains(&block.id) {
+                errors.push(ControlFlowError {
+                    block: block.id,
+                    message: format!(
+                        "header does not dominate its merge block %{:?}",
+                        merge_block_id
+                    ),
+                });
+            }
+            if let StructuredMerge::Loop { continue_target, .. } = merge {
+                if !self.blocks.contains_key(&continue_target) {
+                    errors.push(ControlFlowError {
+                        block: block.id,
+                        message: format!(
+                            "continue target %{:?} is not a block in this function",
+                            continue_target
+                        ),
+                    });
+                } else if !dominators[&continue_target].contains(&block.id) {
+                    errors.push(ControlFlowError {
+                        block: block.id,
+                        message: format!(
+                            "loop header does not dominate its continue target %{:?}",
+                            continue_target
+                        ),
+                    });
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn is_terminator(instruction: &spirv_parser::Instruction) -> bool {
+    matches!(
+        instruction,
+        spirv_parser::Instruction::Branch(_)
+            | spirv_parser::Instruction::BranchConditional(_)
+            | spirv_parser::Instruction::Switch32(_)
+            | spirv_parser::Instruction::Switch64(_)
+            | spirv_parser::Instruction::Return(_)
+            | spirv_parser::Instruction::ReturnValue(_)
+            | spirv_parser::Instruction::Kill(_)
+            | spirv_parser::Instruction::Unreachable(_)
+    )
+}
+
+fn terminator_successors(instruction: &spirv_parser::Instruction) -> Vec<BlockId> {
+    match instruction {
+        spirv_parser::Instruction::Branch(op) => vec![op.target_label],
+        spirv_parser::Instruction::BranchConditional(op) => {
+            vec![op.true_label, op.false_label]
+        }
+        spirv_parser::Instruction::Switch32(op) => std::iter::once(op.default)
+            .chain(op.target.iter().map(|(_, target)| *target))
+            .collect(),
+        spirv_parser::Instruction::Switch64(op) => std::iter::once(op.default)
+            .chain(op.target.iter().map(|(_, target)| *target))
+            .collect(),
+        spirv_parser::Instruction::Return(_)
+        | spirv_parser::Instruction::ReturnValue(_)
+        | spirv_parser::Instruction::Kill(_)
+        | spirv_parser::Instruction::Unreachable(_) => Vec::new(),
+        _ => unreachable!("not a terminator"),
+    }
+}
+
+/// materializes an `OpPhi` by appending a value copy of the selected
+/// operand to the end of each predecessor block (just before its
+/// terminator), since the target IR represents merged values with
+/// explicit per-predecessor copies rather than with phi nodes directly.
+///
+/// `copy_value` is called once per `(predecessor, source_value)` pair
+/// and is responsible for emitting whatever "define `phi_result` as a
+/// copy of `source_value`" instruction the IR builder expects at the end
+/// of `predecessor`.
+pub(crate) fn materialize_phi(
+    incoming: &[(BlockId, spirv_parser::IdRef)],
+    mut copy_value: impl FnMut(BlockId, spirv_parser::IdRef),
+) {
+    for &(predecessor, source_value) in incoming {
+        copy_value(predecessor, source_value);
+    }
+}
+
+/// lowers an `OpSwitch` to a linear comparison chain: for each
+/// `(literal, target)` pair in declaration order, emit a branch to
+/// `target` if the selector equals `literal`, falling through to the
+/// next comparison, and finally an unconditional branch to `default`.
+///
+/// `emit_case` is called once per case in order with `(literal, target)`
+/// and is responsible for emitting the compare-and-conditional-branch
+/// IR; `emit_default` is called once at the end.
+pub(crate) fn lower_switch<Literal: Copy>(
+    cases: &[(Literal, BlockId)],
+    default: BlockId,
+    mut emit_case: impl FnMut(Literal, BlockId),
+    mut emit_default: impl FnMut(BlockId),
+) {
+    for &(literal, target) in cases {
+        emit_case(literal, target);
+    }
+    emit_default(default);
+}
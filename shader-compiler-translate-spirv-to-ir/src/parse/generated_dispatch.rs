@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+// @generated by `cargo xtask codegen-spirv-dispatch` from spirv.core.grammar.json.
+// Do not edit by hand -- bump the grammar file and regenerate instead.
+
+/// the mnemonic the grammar gives an opcode, for diagnostics
+pub(crate) fn opcode_name(opcode: u16) -> &'static str {
+    match opcode {
+        0 => "OpNop",
+        1 => "OpUndef",
+        3 => "OpSource",
+        5 => "OpName",
+        6 => "OpMemberName",
+        11 => "OpExtInstImport",
+        12 => "OpExtInst",
+        14 => "OpMemoryModel",
+        15 => "OpEntryPoint",
+        17 => "OpCapability",
+        19 => "OpTypeVoid",
+        20 => "OpTypeBool",
+        21 => "OpTypeInt",
+        22 => "OpTypeFloat",
+        23 => "OpTypeVector",
+        28 => "OpTypeArray",
+        29 => "OpTypeRuntimeArray",
+        30 => "OpTypeStruct",
+        32 => "OpTypePointer",
+        33 => "OpTypeFunction",
+        43 => "OpConstant",
+        54 => "OpFunction",
+        55 => "OpFunctionParameter",
+        56 => "OpFunctionEnd",
+        57 => "OpFunctionCall",
+        59 => "OpVariable",
+        61 => "OpLoad",
+        62 => "OpStore",
+        65 => "OpAccessChain",
+        71 => "OpDecorate",
+        72 => "OpMemberDecorate",
+        79 => "OpVectorShuffle",
+        80 => "OpCompositeConstruct",
+        81 => "OpCompositeExtract",
+        128 => "OpIAdd",
+        129 => "OpFAdd",
+        130 => "OpISub",
+        133 => "OpFMul",
+        248 => "OpLabel",
+        249 => "OpBranch",
+        250 => "OpBranchConditional",
+        253 => "OpReturn",
+        254 => "OpReturnValue",
+        255 => "OpUnreachable",
+        246 => "OpLoopMerge",
+        247 => "OpSelectionMerge",
+        245 => "OpPhi",
+        252 => "OpKill",
+        _ => "<opcode not in spirv.core.grammar.json>",
+    }
+}
+
+/// the number of required operands (after `IdResultType`/`IdResult`, if present)
+/// an opcode's grammar entry declares, not counting optional/variadic tail operands
+pub(crate) fn min_operand_count(opcode: u16) -> usize {
+    match opcode {
+        0 => 0,
+        1 => 0,
+        3 => 2,
+        5 => 2,
+        6 => 3,
+        11 => 1,
+        12 => 2,
+        14 => 2,
+        15 => 3,
+        17 => 1,
+        19 => 0,
+        20 => 0,
+        21 => 2,
+        22 => 1,
+        23 => 2,
+        28 => 2,
+        29 => 1,
+        30 => 0,
+        32 => 2,
+        33 => 1,
+        43 => 1,
+        54 => 2,
+        55 => 0,
+        56 => 0,
+        57 => 1,
+        59 => 1,
+        61 => 1,
+        62 => 2,
+        65 => 1,
+        71 => 2,
+        72 => 3,
+        79 => 2,
+        80 => 0,
+        81 => 1,
+        128 => 2,
+        129 => 2,
+        130 => 2,
+        133 => 2,
+        248 => 0,
+        249 => 1,
+        250 => 3,
+        253 => 0,
+        254 => 1,
+        255 => 0,
+        246 => 3,
+        247 => 2,
+        245 => 0,
+        252 => 0,
+        _ => 0,
+    }
+}
+
+/// checks `operand_count` against the grammar's required-operand count for `opcode`
+pub(crate) fn validate_operand_count(
+    opcode: u16,
+    operand_count: usize,
+) -> crate::TranslationResult<()> {
+    let min = min_operand_count(opcode);
+    if operand_count < min {
+        return Err(crate::errors::InvalidOperandCount {
+            opcode,
+            min_operand_count: min,
+            operand_count,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// the single shared error path every opcode without a hand-written handler falls back to,
+/// replacing what would otherwise be one `todo!()` call site per unimplemented opcode
+pub(crate) fn unimplemented_opcode<T>(opcode: u16) -> crate::TranslationResult<T> {
+    Err(crate::errors::UnimplementedInstruction { opcode }.into())
+}
@@ -0,0 +1,254 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! `OpenCL.std printf`: since kazan executes shaders entirely in
+//! software, `printf` is lowered to an actual host-side write rather
+//! than left as a no-op -- this is the format-string parser, the
+//! per-work-item argument formatter, and the synchronized [`PrintfSink`]
+//! the formatted text is written to.
+
+use std::fmt;
+use std::sync::Mutex;
+
+/// one variadic argument to `printf`, already widened/decoded from its SPIR-V operand to a host-native type
+#[derive(Clone, Debug)]
+pub(crate) enum PrintfValue {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+}
+
+/// an argument as consumed from the variadic list: a scalar, or -- for OpenCL's `%vNf`-style vector conversions -- `N` components of the same underlying value
+#[derive(Clone, Debug)]
+pub(crate) enum PrintfArgument {
+    Scalar(PrintfValue),
+    Vector(Vec<PrintfValue>),
+}
+
+/// a malformed format string or argument-list mismatch; `printf` reports these to the kernel as a `-1` return rather than failing translation
+#[derive(Debug)]
+pub(crate) enum PrintfError {
+    /// the format string ended in the middle of a `%` conversion specifier
+    UnterminatedConversion,
+    /// a conversion specifier kazan doesn't recognize
+    UnknownConversion(char),
+    /// a vector conversion's component count (`%vN...`) wasn't `2`, `3`, `4`, `8`, or `16`, the only vector widths OpenCL C allows
+    InvalidVectorWidth(u32),
+    /// ran out of variadic arguments before the format string did
+    TooFewArguments,
+    /// the next argument's type didn't match what the conversion specifier expected
+    ArgumentTypeMismatch,
+}
+
+impl fmt::Display for PrintfError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnterminatedConversion => write!(formatter, "unterminated printf conversion specifier"),
+            Self::UnknownConversion(conversion) => write!(formatter, "unknown printf conversion: %{}", conversion),
+            Self::InvalidVectorWidth(width) => write!(formatter, "invalid printf vector width: {}", width),
+            Self::TooFewArguments => write!(formatter, "not enough arguments passed to printf"),
+            Self::ArgumentTypeMismatch => write!(formatter, "printf argument type doesn't match its conversion specifier"),
+        }
+    }
+}
+
+impl std::error::Error for PrintfError {}
+
+/// decodes a NUL-terminated byte string read from the module's constant data, stopping at the first `\0`
+pub(crate) fn read_c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// one parsed `%...` conversion specifier, everything between (and including) the `%` and the final conversion character
+struct ConversionSpec {
+    flags: String,
+    width: Option<usize>,
+    precision: Option<usize>,
+    /// the vector component count from OpenCL's `%vN...` extension, e.g. `4` in `%v4f`
+    vector_width: Option<u32>,
+    conversion: char,
+}
+
+/// formats `format` against `args`, in the style of C's `printf` plus OpenCL C's `%vN...` vector extension
+pub(crate) fn format_printf(format: &str, args: &[PrintfArgument]) -> Result<String, PrintfError> {
+    let mut output = String::with_capacity(format.len());
+    let mut args = args.iter();
+    let mut chars = format.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            output.push(ch);
+            continue;
+        }
+        if chars.peek() == Some(&'%') {
+            chars.next();
+            output.push('%');
+            continue;
+        }
+        let spec = parse_conversion_spec(&mut chars)?;
+        let argument = args.next().ok_or(PrintfError::TooFewArguments)?;
+        format_conversion(&mut output, &spec, argument)?;
+    }
+    Ok(output)
+}
+
+fn parse_conversion_spec(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Result<ConversionSpec, PrintfError> {
+    let mut flags = String::new();
+    while matches!(chars.peek(), Some('-') | Some('+') | Some(' ') | Some('0') | Some('#')) {
+        flags.push(chars.next().unwrap());
+    }
+    let width = parse_decimal(chars);
+    let precision = if chars.peek() == Some(&'.') {
+        chars.next();
+        Some(parse_decimal(chars).unwrap_or(0))
+    } else {
+        None
+    };
+    let vector_width = if chars.peek() == Some(&'v') {
+        chars.next();
+        let width = parse_decimal(chars).ok_or(PrintfError::UnterminatedConversion)? as u32;
+        if !matches!(width, 2 | 3 | 4 | 8 | 16) {
+            return Err(PrintfError::InvalidVectorWidth(width));
+        }
+        Some(width)
+    } else {
+        None
+    };
+    // length modifiers (hh, h, l, ll) don't change the resulting Rust
+    // formatting, since every integer argument already arrives widened
+    // to `i64`/`u64`; skip over them.
+    while matches!(chars.peek(), Some('h') | Some('l') | Some('L')) {
+        chars.next();
+    }
+    let conversion = chars.next().ok_or(PrintfError::UnterminatedConversion)?;
+    Ok(ConversionSpec { flags, width, precision, vector_width, conversion })
+}
+
+fn parse_decimal(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<usize> {
+    let mut digits = String::new();
+    while matches!(chars.peek(), Some(c) if c.is_ascii_digit()) {
+        digits.push(chars.next().unwrap());
+    }
+    digits.parse().ok()
+}
+
+fn format_conversion(output: &mut String, spec: &ConversionSpec, argument: &PrintfArgument) -> Result<(), PrintfError> {
+    match spec.vector_width {
+        Some(width) => {
+            let components = match argument {
+                PrintfArgument::Vector(components) => components,
+                PrintfArgument::Scalar(_) => return Err(PrintfError::ArgumentTypeMismatch),
+            };
+            if components.len() != width as usize {
+                return Err(PrintfError::ArgumentTypeMismatch);
+            }
+            for (index, component) in components.iter().enumerate() {
+                if index != 0 {
+                    output.push(',');
+                }
+                format_scalar_conversion(output, spec, component)?;
+            }
+            Ok(())
+        }
+        None => {
+            let value = match argument {
+                PrintfArgument::Scalar(value) => value,
+                PrintfArgument::Vector(_) => return Err(PrintfError::ArgumentTypeMismatch),
+            };
+            format_scalar_conversion(output, spec, value)
+        }
+    }
+}
+
+fn format_scalar_conversion(output: &mut String, spec: &ConversionSpec, value: &PrintfValue) -> Result<(), PrintfError> {
+    let left_justify = spec.flags.contains('-');
+    let zero_pad = spec.flags.contains('0') && !left_justify;
+    let show_sign = spec.flags.contains('+');
+    let formatted = match (spec.conversion, value) {
+        ('d', &PrintfValue::Int(value)) | ('i', &PrintfValue::Int(value)) => {
+            if show_sign && value >= 0 {
+                format!("+{}", value)
+            } else {
+                format!("{}", value)
+            }
+        }
+        ('u', &PrintfValue::UInt(value)) => format!("{}", value),
+        ('o', &PrintfValue::UInt(value)) => format!("{:o}", value),
+        ('x', &PrintfValue::UInt(value)) => format!("{:x}", value),
+        ('X', &PrintfValue::UInt(value)) => format!("{:X}", value),
+        ('f', &PrintfValue::Float(value)) | ('F', &PrintfValue::Float(value)) => {
+            format!("{:.*}", spec.precision.unwrap_or(6), value)
+        }
+        ('e', &PrintfValue::Float(value)) => format!("{:.*e}", spec.precision.unwrap_or(6), value),
+        ('E', &PrintfValue::Float(value)) => format!("{:.*E}", spec.precision.unwrap_or(6), value),
+        ('g', &PrintfValue::Float(value)) | ('G', &PrintfValue::Float(value)) => format!("{}", value),
+        ('c', &PrintfValue::Int(value)) => (value as u8 as char).to_string(),
+        ('s', PrintfValue::Str(value)) => match spec.precision {
+            Some(precision) => value.chars().take(precision).collect(),
+            None => value.clone(),
+        },
+        _ => return Err(PrintfError::ArgumentTypeMismatch),
+    };
+    let width = spec.width.unwrap_or(0);
+    if formatted.len() >= width {
+        output.push_str(&formatted);
+    } else if left_justify {
+        output.push_str(&formatted);
+        output.extend(std::iter::repeat(' ').take(width - formatted.len()));
+    } else if zero_pad {
+        let (sign, digits) = match formatted.strip_prefix(['-', '+']) {
+            Some(digits) => (&formatted[..1], digits),
+            None => ("", formatted.as_str()),
+        };
+        output.push_str(sign);
+        output.extend(std::iter::repeat('0').take(width - formatted.len()));
+        output.push_str(digits);
+    } else {
+        output.extend(std::iter::repeat(' ').take(width - formatted.len()));
+        output.push_str(&formatted);
+    }
+    Ok(())
+}
+
+/// where a `printf` call's formatted output goes; the execution context's default is [`StdoutPrintfSink`], overridable so embedders can capture kernel output instead
+pub(crate) trait PrintfSink: Send + Sync {
+    fn write(&self, text: &str);
+}
+
+/// the default [`PrintfSink`]: writes to the host process's stdout, serializing concurrent writers so output from different work-items can't interleave mid-line
+pub(crate) struct StdoutPrintfSink {
+    lock: Mutex<()>,
+}
+
+impl StdoutPrintfSink {
+    pub(crate) fn new() -> Self {
+        Self { lock: Mutex::new(()) }
+    }
+}
+
+impl Default for StdoutPrintfSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrintfSink for StdoutPrintfSink {
+    fn write(&self, text: &str) {
+        use std::io::Write;
+        let _guard = self.lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        print!("{}", text);
+        let _ = std::io::stdout().flush();
+    }
+}
+
+/// `OpenCL.std printf(format, ...)`: formats `args` against `format` and writes the result to `sink` in one shot so concurrent calls don't interleave; returns `0` on success and `-1` on a malformed format string or argument mismatch, per the OpenCL C spec
+pub(crate) fn printf(sink: &dyn PrintfSink, format: &str, args: &[PrintfArgument]) -> i32 {
+    match format_printf(format, args) {
+        Ok(text) => {
+            sink.write(&text);
+            0
+        }
+        Err(_) => -1,
+    }
+}
@@ -0,0 +1,492 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! emulates the `SPV_KHR_shader_ballot` and `GroupNonUniform` subgroup
+//! surface by running each invocation of a subgroup as one lane of a
+//! fixed-width SIMD block, tracked as a [`LaneMask`] of the lanes that
+//! are active (i.e. not disabled by non-uniform control flow) at the
+//! point a given `OpGroupNonUniform*`/ballot instruction executes.
+//!
+//! every op in this module reduces to one of a handful of primitives
+//! over that mask: ballot (predicate -> mask), cross-lane gather
+//! (shuffle/broadcast), or a masked fold over the active lanes in
+//! ascending lane order (the arithmetic and logical group operations;
+//! reductions and scans are sequential folds, not tree reductions or
+//! Hillis-Steele scans).
+
+use std::fmt;
+
+/// the maximum subgroup size this emulation supports: four 32-bit words
+/// of lanes, matching `OpGroupNonUniformBallot`'s fixed `vec4<u32>` result.
+pub(crate) const MAX_SUBGROUP_SIZE: u32 = 128;
+
+/// the set of lanes active in a subgroup operation, as the
+/// `vec4<u32>` bitmask `OpGroupNonUniformBallot` produces: bit `i` of
+/// word `i / 32` is set iff lane `i` is active.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Hash)]
+pub(crate) struct LaneMask(pub(crate) [u32; 4]);
+
+impl LaneMask {
+    pub(crate) const EMPTY: LaneMask = LaneMask([0; 4]);
+
+    /// the mask with exactly lanes `0..subgroup_size` active, used for
+    /// subgroup-scoped operations with no predicate of their own (e.g. `OpGroupNonUniformBroadcast`).
+    pub(crate) fn full(subgroup_size: u32) -> Self {
+        let mut words = [0u32; 4];
+        for (i, word) in words.iter_mut().enumerate() {
+            let lo = (i as u32) * 32;
+            let hi = lo + 32;
+            *word = if subgroup_size <= lo {
+                0
+            } else if subgroup_size >= hi {
+                u32::MAX
+            } else {
+                (1u32 << (subgroup_size - lo)) - 1
+            };
+        }
+        LaneMask(words)
+    }
+
+    /// `OpGroupNonUniformBallot`: the mask with lane `i` active iff `predicate(i)` is true and `i` is in `active`
+    pub(crate) fn ballot(active: LaneMask, predicate: impl Fn(u32) -> bool) -> Self {
+        let mut result = LaneMask::EMPTY;
+        for lane in active.iter_set_lanes() {
+            if predicate(lane) {
+                result.set(lane);
+            }
+        }
+        result
+    }
+
+    pub(crate) fn is_set(self, lane: u32) -> bool {
+        self.0[(lane / 32) as usize] & (1 << (lane % 32)) != 0
+    }
+
+    fn set(&mut self, lane: u32) {
+        self.0[(lane / 32) as usize] |= 1 << (lane % 32);
+    }
+
+    /// `OpGroupNonUniformInverseBallot`: whether the invocation's own lane is active in `self`
+    pub(crate) fn inverse_ballot(self, own_lane: u32) -> bool {
+        self.is_set(own_lane)
+    }
+
+    /// `OpGroupNonUniformBallotBitExtract`: whether lane `index` is active in `self`
+    pub(crate) fn ballot_bit_extract(self, index: u32) -> bool {
+        self.is_set(index)
+    }
+
+    /// `OpGroupNonUniformBallotFindLSB`: the lowest-numbered active lane, if any
+    pub(crate) fn find_lsb(self) -> Option<u32> {
+        self.0.iter().enumerate().find_map(|(word_index, &word)| {
+            if word == 0 {
+                None
+            } else {
+                Some(word_index as u32 * 32 + word.trailing_zeros())
+            }
+        })
+    }
+
+    /// `OpGroupNonUniformBallotFindMSB`: the highest-numbered active lane, if any
+    pub(crate) fn find_msb(self) -> Option<u32> {
+        self.0
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(word_index, &word)| {
+                if word == 0 {
+                    None
+                } else {
+                    Some(word_index as u32 * 32 + (31 - word.leading_zeros()))
+                }
+            })
+    }
+
+    fn iter_set_lanes(self) -> impl Iterator<Item = u32> {
+        (0..MAX_SUBGROUP_SIZE).filter(move |&lane| self.is_set(lane))
+    }
+
+    /// `OpGroupNonUniformBallotBitCount` with `GroupOperation::Reduce`: the total number of active lanes
+    pub(crate) fn bit_count_reduce(self) -> u32 {
+        self.0.iter().map(|word| word.count_ones()).sum()
+    }
+
+    /// `OpGroupNonUniformBallotBitCount` with `GroupOperation::InclusiveScan`: the number of active lanes `<= lane`, inclusive
+    pub(crate) fn bit_count_inclusive_scan(self, lane: u32) -> u32 {
+        LaneMask::full(lane + 1).and(self).bit_count_reduce()
+    }
+
+    /// `OpGroupNonUniformBallotBitCount` with `GroupOperation::ExclusiveScan`: the number of active lanes `< lane`
+    pub(crate) fn bit_count_exclusive_scan(self, lane: u32) -> u32 {
+        if lane == 0 {
+            0
+        } else {
+            LaneMask::full(lane).and(self).bit_count_reduce()
+        }
+    }
+
+    fn and(self, rhs: LaneMask) -> LaneMask {
+        let mut words = self.0;
+        for (word, &rhs_word) in words.iter_mut().zip(&rhs.0) {
+            *word &= rhs_word;
+        }
+        LaneMask(words)
+    }
+}
+
+impl fmt::Debug for LaneMask {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LaneMask({:08x}_{:08x}_{:08x}_{:08x})",
+            self.0[3], self.0[2], self.0[1], self.0[0]
+        )
+    }
+}
+
+/// `OpGroupNonUniformBroadcast`: the value lane `id` held, regardless of whether `id` is active
+pub(crate) fn broadcast<T: Copy>(lanes: &[T], id: u32) -> T {
+    lanes[id as usize]
+}
+
+/// `OpGroupNonUniformBroadcastFirst`: the value held by the lowest-numbered active lane
+pub(crate) fn broadcast_first<T: Copy>(lanes: &[T], active: LaneMask) -> T {
+    let first_active = active.find_lsb().expect("broadcast_first requires at least one active lane");
+    lanes[first_active as usize]
+}
+
+/// `OpGroupNonUniformShuffle`: the value held by lane `id`
+pub(crate) fn shuffle<T: Copy>(lanes: &[T], id: u32) -> T {
+    lanes[id as usize]
+}
+
+/// `OpGroupNonUniformShuffleXor`: the value held by `own_lane ^ mask`
+pub(crate) fn shuffle_xor<T: Copy>(lanes: &[T], own_lane: u32, mask: u32) -> T {
+    lanes[(own_lane ^ mask) as usize]
+}
+
+/// `OpGroupNonUniformShuffleUp`: the value held by `own_lane - delta`
+pub(crate) fn shuffle_up<T: Copy>(lanes: &[T], own_lane: u32, delta: u32) -> T {
+    lanes[(own_lane - delta) as usize]
+}
+
+/// `OpGroupNonUniformShuffleDown`: the value held by `own_lane + delta`
+pub(crate) fn shuffle_down<T: Copy>(lanes: &[T], own_lane: u32, delta: u32) -> T {
+    lanes[(own_lane + delta) as usize]
+}
+
+/// `OpGroupNonUniformQuadBroadcast`: the value held by lane `index` within the calling lane's 2x2 quad
+pub(crate) fn quad_broadcast<T: Copy>(lanes: &[T], own_lane: u32, index: u32) -> T {
+    let quad_base = own_lane - own_lane % 4;
+    lanes[(quad_base + index) as usize]
+}
+
+/// the four `OpGroupNonUniformQuadSwap` directions: swap with the horizontally, vertically, or diagonally adjacent lane of the calling lane's 2x2 quad
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum QuadDirection {
+    Horizontal,
+    Vertical,
+    Diagonal,
+}
+
+/// `OpGroupNonUniformQuadSwap`
+pub(crate) fn quad_swap<T: Copy>(lanes: &[T], own_lane: u32, direction: QuadDirection) -> T {
+    let quad_base = own_lane - own_lane % 4;
+    let offset_in_quad = own_lane % 4;
+    let partner_offset = match direction {
+        QuadDirection::Horizontal => offset_in_quad ^ 1,
+        QuadDirection::Vertical => offset_in_quad ^ 2,
+        QuadDirection::Diagonal => offset_in_quad ^ 3,
+    };
+    lanes[(quad_base + partner_offset) as usize]
+}
+
+/// selects `Reduce`/`InclusiveScan`/`ExclusiveScan` behavior for the arithmetic and logical group operations, matching the SPIR-V `GroupOperation` operand
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum GroupOperation {
+    Reduce,
+    InclusiveScan,
+    ExclusiveScan,
+}
+
+/// one of the arithmetic/logical group operations (`OpGroupNonUniformIAdd`, `FMin`, `BitwiseAnd`, `LogicalOr`, etc.), each an
+/// associative, identity-having combine over active lanes' values
+pub(crate) trait Combine: Copy {
+    /// the value that leaves every other value unchanged when combined with it (e.g. `0` for add, all-ones for `BitwiseAnd`)
+    fn identity() -> Self;
+    fn combine(self, rhs: Self) -> Self;
+}
+
+/// `Reduce`: combine every active lane's value into one
+pub(crate) fn reduce<T: Combine>(lanes: &[T], active: LaneMask) -> T {
+    active
+        .iter_set_lanes()
+        .map(|lane| lanes[lane as usize])
+        .fold(T::identity(), Combine::combine)
+}
+
+/// `InclusiveScan`: the prefix combine of active lanes `<= lane`, inclusive, folded in ascending lane order
+pub(crate) fn inclusive_scan<T: Combine>(lanes: &[T], active: LaneMask, lane: u32) -> T {
+    active
+        .iter_set_lanes()
+        .filter(|&l| l <= lane)
+        .map(|l| lanes[l as usize])
+        .fold(T::identity(), Combine::combine)
+}
+
+/// `ExclusiveScan`: the prefix combine of active lanes `< lane`, folded in ascending lane order
+pub(crate) fn exclusive_scan<T: Combine>(lanes: &[T], active: LaneMask, lane: u32) -> T {
+    active
+        .iter_set_lanes()
+        .filter(|&l| l < lane)
+        .map(|l| lanes[l as usize])
+        .fold(T::identity(), Combine::combine)
+}
+
+/// dispatches a [`Combine`] reduction/scan per [`GroupOperation`]; shared by every arithmetic/logical `OpGroupNonUniform*` lowering
+pub(crate) fn group_operation<T: Combine>(
+    lanes: &[T],
+    active: LaneMask,
+    operation: GroupOperation,
+    own_lane: u32,
+) -> T {
+    match operation {
+        GroupOperation::Reduce => reduce(lanes, active),
+        GroupOperation::InclusiveScan => inclusive_scan(lanes, active, own_lane),
+        GroupOperation::ExclusiveScan => exclusive_scan(lanes, active, own_lane),
+    }
+}
+
+macro_rules! impl_combine {
+    ($ty:ty, $identity:expr, |$lhs:ident, $rhs:ident| $combine:expr) => {
+        impl Combine for $ty {
+            fn identity() -> Self {
+                $identity
+            }
+            fn combine(self, rhs: Self) -> Self {
+                let $lhs = self;
+                let $rhs = rhs;
+                $combine
+            }
+        }
+    };
+}
+
+impl_combine!(i32, 0, |a, b| a.wrapping_add(b)); // IAdd
+impl_combine!(f32, 0.0, |a, b| a + b); // FAdd
+
+/// `OpGroupNonUniformIMul`/`FMul` use this newtype instead of the bare `i32`/`f32` impls so multiplication and addition can coexist as distinct [`Combine`] instances for the same underlying type
+#[derive(Copy, Clone)]
+pub(crate) struct Mul<T>(pub(crate) T);
+impl_combine!(Mul<i32>, Mul(1), |a, b| Mul(a.0.wrapping_mul(b.0)));
+impl_combine!(Mul<f32>, Mul(1.0), |a, b| Mul(a.0 * b.0));
+
+#[derive(Copy, Clone)]
+pub(crate) struct UMin(pub(crate) u32);
+impl_combine!(UMin, UMin(u32::MAX), |a, b| UMin(a.0.min(b.0)));
+#[derive(Copy, Clone)]
+pub(crate) struct SMin(pub(crate) i32);
+impl_combine!(SMin, SMin(i32::MAX), |a, b| SMin(a.0.min(b.0)));
+#[derive(Copy, Clone)]
+pub(crate) struct FMin(pub(crate) f32);
+impl_combine!(FMin, FMin(f32::INFINITY), |a, b| FMin(a.0.min(b.0)));
+#[derive(Copy, Clone)]
+pub(crate) struct UMax(pub(crate) u32);
+impl_combine!(UMax, UMax(0), |a, b| UMax(a.0.max(b.0)));
+#[derive(Copy, Clone)]
+pub(crate) struct SMax(pub(crate) i32);
+impl_combine!(SMax, SMax(i32::MIN), |a, b| SMax(a.0.max(b.0)));
+#[derive(Copy, Clone)]
+pub(crate) struct FMax(pub(crate) f32);
+impl_combine!(FMax, FMax(f32::NEG_INFINITY), |a, b| FMax(a.0.max(b.0)));
+
+#[derive(Copy, Clone)]
+pub(crate) struct BitwiseAnd(pub(crate) u32);
+impl_combine!(BitwiseAnd, BitwiseAnd(u32::MAX), |a, b| BitwiseAnd(a.0 & b.0));
+#[derive(Copy, Clone)]
+pub(crate) struct BitwiseOr(pub(crate) u32);
+impl_combine!(BitwiseOr, BitwiseOr(0), |a, b| BitwiseOr(a.0 | b.0));
+#[derive(Copy, Clone)]
+pub(crate) struct BitwiseXor(pub(crate) u32);
+impl_combine!(BitwiseXor, BitwiseXor(0), |a, b| BitwiseXor(a.0 ^ b.0));
+#[derive(Copy, Clone)]
+pub(crate) struct LogicalAnd(pub(crate) bool);
+impl_combine!(LogicalAnd, LogicalAnd(true), |a, b| LogicalAnd(a.0 && b.0));
+#[derive(Copy, Clone)]
+pub(crate) struct LogicalOr(pub(crate) bool);
+impl_combine!(LogicalOr, LogicalOr(false), |a, b| LogicalOr(a.0 || b.0));
+#[derive(Copy, Clone)]
+pub(crate) struct LogicalXor(pub(crate) bool);
+impl_combine!(LogicalXor, LogicalXor(false), |a, b| LogicalXor(a.0 != b.0));
+
+use crate::{
+    parse::{functions::TranslationStateParsingFunctionBody, ParseInstruction},
+    TranslationResult,
+};
+
+macro_rules! impl_ballot_instruction {
+    ($opname:ident, |$state:ident, $self:ident| $body:expr) => {
+        impl ParseInstruction for spirv_parser::$opname {
+            fn parse_in_types_constants_globals_section<'g, 'i>(
+                &'i self,
+                _state: &mut crate::parse::TranslationStateParsingTypesConstantsAndGlobals<'g, 'i>,
+            ) -> TranslationResult<()> {
+                todo!(concat!(stringify!($opname), " is only valid in a function body"))
+            }
+            fn parse_in_function_body<'g, 'i>(
+                &'i self,
+                $state: &mut TranslationStateParsingFunctionBody<'g, 'i>,
+            ) -> TranslationResult<()> {
+                let $self = self;
+                $body
+            }
+        }
+    };
+}
+
+impl_ballot_instruction!(OpGroupNonUniformBallot, |state, self_| {
+    let active = state.get_active_lane_mask(self_.execution)?;
+    let mask = LaneMask::ballot(active, |lane| state.get_lane_bool_value(self_.predicate, lane));
+    state.define_lane_mask_value(self_.id_result_type, self_.id_result, mask)
+});
+
+impl_ballot_instruction!(OpGroupNonUniformInverseBallot, |state, self_| {
+    let mask = state.get_lane_mask_value(self_.value)?;
+    let own_lane = state.own_lane();
+    state.define_bool_value(self_.id_result_type, self_.id_result, mask.inverse_ballot(own_lane))
+});
+
+impl_ballot_instruction!(OpGroupNonUniformBallotBitExtract, |state, self_| {
+    let mask = state.get_lane_mask_value(self_.value)?;
+    let index = state.get_u32_value(self_.index)?;
+    state.define_bool_value(self_.id_result_type, self_.id_result, mask.ballot_bit_extract(index))
+});
+
+impl_ballot_instruction!(OpGroupNonUniformBallotFindLSB, |state, self_| {
+    let mask = state.get_lane_mask_value(self_.value)?;
+    state.define_u32_value(
+        self_.id_result_type,
+        self_.id_result,
+        mask.find_lsb().expect("BallotFindLSB requires a non-empty mask"),
+    )
+});
+
+impl_ballot_instruction!(OpGroupNonUniformBallotFindMSB, |state, self_| {
+    let mask = state.get_lane_mask_value(self_.value)?;
+    state.define_u32_value(
+        self_.id_result_type,
+        self_.id_result,
+        mask.find_msb().expect("BallotFindMSB requires a non-empty mask"),
+    )
+});
+
+impl_ballot_instruction!(OpGroupNonUniformBallotBitCount, |state, self_| {
+    let mask = state.get_lane_mask_value(self_.value)?;
+    let count = match state.get_group_operation(self_.group_operation)? {
+        GroupOperation::Reduce => mask.bit_count_reduce(),
+        GroupOperation::InclusiveScan => mask.bit_count_inclusive_scan(state.own_lane()),
+        GroupOperation::ExclusiveScan => mask.bit_count_exclusive_scan(state.own_lane()),
+    };
+    state.define_u32_value(self_.id_result_type, self_.id_result, count)
+});
+
+impl_ballot_instruction!(OpGroupNonUniformBroadcast, |state, self_| {
+    let lanes = state.get_lane_values(self_.value)?;
+    let id = state.get_u32_value(self_.id)?;
+    state.define_value(self_.id_result_type, self_.id_result, broadcast(&lanes, id))
+});
+
+impl_ballot_instruction!(OpGroupNonUniformBroadcastFirst, |state, self_| {
+    let lanes = state.get_lane_values(self_.value)?;
+    let active = state.get_active_lane_mask(self_.execution)?;
+    state.define_value(self_.id_result_type, self_.id_result, broadcast_first(&lanes, active))
+});
+
+impl_ballot_instruction!(OpGroupNonUniformShuffle, |state, self_| {
+    let lanes = state.get_lane_values(self_.value)?;
+    let id = state.get_u32_value(self_.id)?;
+    state.define_value(self_.id_result_type, self_.id_result, shuffle(&lanes, id))
+});
+
+impl_ballot_instruction!(OpGroupNonUniformShuffleXor, |state, self_| {
+    let lanes = state.get_lane_values(self_.value)?;
+    let mask = state.get_u32_value(self_.mask)?;
+    let own_lane = state.own_lane();
+    state.define_value(self_.id_result_type, self_.id_result, shuffle_xor(&lanes, own_lane, mask))
+});
+
+impl_ballot_instruction!(OpGroupNonUniformShuffleUp, |state, self_| {
+    let lanes = state.get_lane_values(self_.value)?;
+    let delta = state.get_u32_value(self_.delta)?;
+    let own_lane = state.own_lane();
+    state.define_value(self_.id_result_type, self_.id_result, shuffle_up(&lanes, own_lane, delta))
+});
+
+impl_ballot_instruction!(OpGroupNonUniformShuffleDown, |state, self_| {
+    let lanes = state.get_lane_values(self_.value)?;
+    let delta = state.get_u32_value(self_.delta)?;
+    let own_lane = state.own_lane();
+    state.define_value(self_.id_result_type, self_.id_result, shuffle_down(&lanes, own_lane, delta))
+});
+
+impl_ballot_instruction!(OpGroupNonUniformQuadBroadcast, |state, self_| {
+    let lanes = state.get_lane_values(self_.value)?;
+    let index = state.get_u32_value(self_.index)?;
+    let own_lane = state.own_lane();
+    state.define_value(self_.id_result_type, self_.id_result, quad_broadcast(&lanes, own_lane, index))
+});
+
+impl_ballot_instruction!(OpGroupNonUniformQuadSwap, |state, self_| {
+    let lanes = state.get_lane_values(self_.value)?;
+    let direction = state.get_quad_direction(self_.direction)?;
+    let own_lane = state.own_lane();
+    state.define_value(self_.id_result_type, self_.id_result, quad_swap(&lanes, own_lane, direction))
+});
+
+/// implements the arithmetic/logical `OpGroupNonUniform*` family, each sharing
+/// the same shape: an execution scope, a [`GroupOperation`], and one value per lane,
+/// combined via the instruction's [`Combine`] impl.
+macro_rules! impl_group_nonuniform_arithmetic {
+    ($opname:ident, $combine_ty:ty, $wrap:expr, $unwrap:expr) => {
+        impl ParseInstruction for spirv_parser::$opname {
+            fn parse_in_types_constants_globals_section<'g, 'i>(
+                &'i self,
+                _state: &mut crate::parse::TranslationStateParsingTypesConstantsAndGlobals<'g, 'i>,
+            ) -> TranslationResult<()> {
+                todo!(concat!(stringify!($opname), " is only valid in a function body"))
+            }
+            fn parse_in_function_body<'g, 'i>(
+                &'i self,
+                state: &mut TranslationStateParsingFunctionBody<'g, 'i>,
+            ) -> TranslationResult<()> {
+                let active = state.get_active_lane_mask(self.execution)?;
+                let operation = state.get_group_operation(self.group_operation)?;
+                let own_lane = state.own_lane();
+                let lanes: Vec<$combine_ty> = state
+                    .get_lane_values(self.value)?
+                    .into_iter()
+                    .map($wrap)
+                    .collect();
+                let result = group_operation(&lanes, active, operation, own_lane);
+                state.define_value(self.id_result_type, self.id_result, $unwrap(result))
+            }
+        }
+    };
+}
+
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformIAdd, i32, |v| v, |v| v);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformFAdd, f32, |v| v, |v| v);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformIMul, Mul<i32>, Mul, |v: Mul<i32>| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformFMul, Mul<f32>, Mul, |v: Mul<f32>| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformSMin, SMin, SMin, |v: SMin| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformUMin, UMin, UMin, |v: UMin| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformFMin, FMin, FMin, |v: FMin| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformSMax, SMax, SMax, |v: SMax| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformUMax, UMax, UMax, |v: UMax| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformFMax, FMax, FMax, |v: FMax| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformBitwiseAnd, BitwiseAnd, BitwiseAnd, |v: BitwiseAnd| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformBitwiseOr, BitwiseOr, BitwiseOr, |v: BitwiseOr| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformBitwiseXor, BitwiseXor, BitwiseXor, |v: BitwiseXor| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformLogicalAnd, LogicalAnd, LogicalAnd, |v: LogicalAnd| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformLogicalOr, LogicalOr, LogicalOr, |v: LogicalOr| v.0);
+impl_group_nonuniform_arithmetic!(OpGroupNonUniformLogicalXor, LogicalXor, LogicalXor, |v: LogicalXor| v.0);
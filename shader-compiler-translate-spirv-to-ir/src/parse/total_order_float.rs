@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! a total-ordering wrapper around `f32`/`f64`, à la the `ordered-float`
+//! crate, for anywhere the constant-folding/interpreter path needs to
+//! compare or sort floats deterministically. The IEEE 754 partial
+//! order has no answer for `NaN` and makes `+0.0`/`-0.0` compare equal
+//! -- exactly the two properties that make `sort_by`/`Ord`-keyed
+//! caching on raw `f32`/`f64` either panic or silently go
+//! nondeterministic. Wrapping in [`TotalOrderF32`]/[`TotalOrderF64`]
+//! instead gives every bit pattern, signaling/quiet `NaN`s included, a
+//! distinct fixed position: `-NaN < -inf < ... < -0 < +0 < ... < +inf < +NaN`
+//! (`f32::total_cmp`/`f64::total_cmp`'s order).
+
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+macro_rules! total_order_float {
+    ($name:ident, $float:ty) => {
+        #[derive(Copy, Clone, Debug)]
+        pub(crate) struct $name(pub(crate) $float);
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.to_bits().hash(state);
+            }
+        }
+    };
+}
+
+total_order_float!(TotalOrderF32, f32);
+total_order_float!(TotalOrderF64, f64);
@@ -6,7 +6,10 @@ use crate::{
     errors::{
         BlockStructTypeNotAllowedAsMemberOfNonBlockStruct,
         InvalidComponentDecorationOnVariableOrStructMember, MemberDecorationNotAllowed,
-        MissingLocationDecorationOnVariableOrStructMember, TranslationResult,
+        MissingBuiltInDecorationOnBuiltInsStructMember,
+        MissingLocationDecorationOnVariableOrStructMember, MissingOffsetDecorationOnBlockMember,
+        RuntimeArrayMemberNotAllowedInNonBufferBlock, RuntimeArrayMemberNotLast,
+        StructMemberOffsetOverlapsPreviousMember, TranslationResult,
         TypeNotAllowedInUserDefinedVariableInterface,
     },
     io_layout::{io_interface_block_alignment, LOCATION_SIZE_IN_BYTES},
@@ -34,6 +37,11 @@ pub(crate) struct StructMember<'g> {
     pub(crate) member_type_id: spirv_parser::IdRef,
     pub(crate) memory_object_declaration_or_struct_member: MemoryObjectDeclarationOrStructMember,
     pub(crate) variable_or_struct_member: VariableOrStructMember,
+    /// `Some(array_stride)` if this member's type is an `OpTypeRuntimeArray`
+    /// (always unsized, carrying its element stride in bytes); `None` for
+    /// an ordinarily-sized member. Only legal as a struct's last member --
+    /// see `StructKind::Block`'s handling in `get_ir_type_uncached`.
+    pub(crate) runtime_array_stride: Option<u32>,
 }
 
 #[derive(Debug)]
@@ -83,24 +91,273 @@ impl<'g> From<StructType<'g>> for SPIRVType<'g> {
     }
 }
 
+/// round `offset` up to the next multiple of `alignment`
+fn round_up_to_alignment(offset: usize, alignment: Alignment) -> usize {
+    let alignment = alignment.get() as usize;
+    (offset + alignment - 1) / alignment * alignment
+}
+
+/// the per-element stride, in bytes, of a trailing `OpTypeRuntimeArray`
+/// member: its element's natural size rounded up to its own alignment, the
+/// same packing rule ordinary members use between each other.
+///
+/// this is only the arithmetic half of `StructMember::runtime_array_stride`;
+/// the other half -- recognizing that a member's SPIR-V type is an
+/// `OpTypeRuntimeArray` at all and calling this -- belongs at the site that
+/// builds a `StructMember` from a parsed `OpTypeStruct`'s member list, which
+/// isn't part of this file and isn't present anywhere in this tree.
+fn runtime_array_stride(
+    global_state: &GlobalState,
+    element_type: Interned<shader_compiler_ir::Type>,
+    element_alignment: Alignment,
+) -> u32 {
+    round_up_to_alignment(
+        ir_type_size_in_bytes(global_state, element_type),
+        element_alignment,
+    ) as u32
+}
+
+/// the size in bytes of an already-translated IR type, used to compute
+/// member offsets when laying out a struct's members
+///
+/// this mirrors the field shapes `StructType`'s own `get_ir_type_with_state`
+/// below constructs (in particular that a nested, already-interned
+/// `shader_compiler_ir::StructType` carries its own total `size`), rather
+/// than re-deriving sizes from first principles for every `Type` variant
+fn ir_type_size_in_bytes(
+    global_state: &GlobalState,
+    ir_type: Interned<shader_compiler_ir::Type>,
+) -> usize {
+    match &*ir_type {
+        shader_compiler_ir::Type::Integer(integer_type) => match integer_type {
+            shader_compiler_ir::IntegerType::Int8 => 1,
+            shader_compiler_ir::IntegerType::Int16 => 2,
+            shader_compiler_ir::IntegerType::Int32
+            | shader_compiler_ir::IntegerType::RelaxedInt32 => 4,
+            shader_compiler_ir::IntegerType::Int64 => 8,
+        },
+        shader_compiler_ir::Type::Float(float_type) => match float_type {
+            shader_compiler_ir::FloatType::Float16 => 2,
+            shader_compiler_ir::FloatType::Float32
+            | shader_compiler_ir::FloatType::RelaxedFloat32 => 4,
+            shader_compiler_ir::FloatType::Float64 => 8,
+        },
+        shader_compiler_ir::Type::Bool(_) => 4,
+        shader_compiler_ir::Type::Pointer(_) => {
+            global_state.target_properties().pointer_size_in_bytes()
+        }
+        shader_compiler_ir::Type::Vector(vector_type) => {
+            ir_type_size_in_bytes(global_state, vector_type.element) * vector_type.len
+        }
+        shader_compiler_ir::Type::Array(array_type) => {
+            ir_type_size_in_bytes(global_state, array_type.element) * array_type.len
+        }
+        shader_compiler_ir::Type::Struct(struct_type) => match struct_type.size {
+            StructSize::Fixed { size } => size,
+            StructSize::Unsized { .. } => {
+                unreachable!("an unsized struct can't be used as an ordinary struct member")
+            }
+        },
+        shader_compiler_ir::Type::Opaque(_) => {
+            unreachable!("an opaque type can't be used as a struct member")
+        }
+    }
+}
+
+impl<'g> StructType<'g> {
+    /// the uncached body of `get_ir_type_with_state`, called at most once
+    /// per distinct `StructType` -- see that method for the memoization
+    fn get_ir_type_uncached(
+        &self,
+        state: &mut GetIrTypeState<'g>,
+    ) -> TranslationResult<Option<Interned<'g, shader_compiler_ir::Type<'g>>>> {
+        match self.kind {
+            StructKind::Generic => {
+                // no explicit layout decorations apply here, so offsets are
+                // packed from each member's own natural alignment/size
+                let mut next_offset = 0;
+                let mut members = Vec::with_capacity(self.members.len());
+                for member in &self.members {
+                    let member_alignment = member.member_type.get_alignment(
+                        state.target_properties(),
+                        state.global_state(),
+                        member.member_type_id,
+                        || self.get_struct_instruction().into(),
+                    )?;
+                    let offset = round_up_to_alignment(next_offset, member_alignment);
+                    let member_type = member
+                        .member_type
+                        .get_ir_type_with_state(state)?
+                        .expect("struct member type must not be void");
+                    next_offset = offset + ir_type_size_in_bytes(state.global_state(), member_type);
+                    members.push(shader_compiler_ir::StructMember {
+                        member_type,
+                        offset,
+                    });
+                }
+                Ok(Some(
+                    shader_compiler_ir::StructType {
+                        alignment: self.get_alignment(
+                            state.target_properties(),
+                            state.global_state(),
+                            self.id,
+                            || self.get_struct_instruction().into(),
+                        )?,
+                        size: StructSize::Fixed { size: next_offset },
+                        members,
+                    }
+                    .intern(state.global_state()),
+                ))
+            }
+            StructKind::Block { is_buffer_block } => {
+                // `Offset` is mandatory on every member of a block (the
+                // SPIR-V validator already enforces this), so the layout
+                // here just needs to honor it and check it's
+                // non-overlapping and increasing, not compute it.
+                //
+                // `ArrayStride`/`MatrixStride` decorations are neither
+                // honored nor checked here: members are interned with their
+                // natural stride, and nothing compares a stride decoration
+                // against it. `shader_compiler_ir::ArrayType` has no stride
+                // field of its own to carry a non-natural one, so a block
+                // member whose stride decoration disagrees with its natural
+                // size is laid out as if it had the natural stride.
+                let mut next_min_offset = 0;
+                let mut members = Vec::with_capacity(self.members.len());
+                // `Some((fixed_size, element_stride))` once the trailing
+                // `OpTypeRuntimeArray` member (the standard SSBO
+                // variable-length-data idiom) has been seen; set only on
+                // the last iteration, since a runtime array is only legal
+                // as a block's last member
+                let mut unsized_tail = None;
+                for (member_index, member) in self.members.iter().enumerate() {
+                    let is_last = member_index + 1 == self.members.len();
+                    let offset = member
+                        .memory_object_declaration_or_struct_member
+                        .offset
+                        .ok_or_else(|| MissingOffsetDecorationOnBlockMember {
+                            type_id: self.id,
+                            member_index: member_index as u32,
+                        })? as usize;
+                    if offset < next_min_offset {
+                        return Err(StructMemberOffsetOverlapsPreviousMember {
+                            type_id: self.id,
+                            member_index: member_index as u32,
+                        }
+                        .into());
+                    }
+                    if let Some(array_stride) = member.runtime_array_stride {
+                        // a trailing OpTypeRuntimeArray is the SSBO
+                        // variable-length-data idiom; it's not legal in a
+                        // uniform block (is_buffer_block == false), which
+                        // must be entirely fixed-size
+                        if !is_buffer_block {
+                            return Err(RuntimeArrayMemberNotAllowedInNonBufferBlock {
+                                type_id: self.id,
+                                member_index: member_index as u32,
+                            }
+                            .into());
+                        }
+                        if !is_last {
+                            return Err(RuntimeArrayMemberNotLast {
+                                type_id: self.id,
+                                member_index: member_index as u32,
+                            }
+                            .into());
+                        }
+                        unsized_tail = Some((offset, array_stride as usize));
+                        continue;
+                    }
+                    let member_type = member
+                        .member_type
+                        .get_ir_type_with_state(state)?
+                        .expect("struct member type must not be void");
+                    next_min_offset =
+                        offset + ir_type_size_in_bytes(state.global_state(), member_type);
+                    members.push(shader_compiler_ir::StructMember {
+                        member_type,
+                        offset,
+                    });
+                }
+                let size = match unsized_tail {
+                    Some((fixed_size, element_stride)) => StructSize::Unsized {
+                        fixed_size,
+                        element_stride,
+                    },
+                    None => StructSize::Fixed {
+                        size: next_min_offset,
+                    },
+                };
+                Ok(Some(
+                    shader_compiler_ir::StructType {
+                        alignment: self.get_alignment(
+                            state.target_properties(),
+                            state.global_state(),
+                            self.id,
+                            || self.get_struct_instruction().into(),
+                        )?,
+                        size,
+                        members,
+                    }
+                    .intern(state.global_state()),
+                ))
+            }
+            StructKind::BuiltIns => {
+                unreachable!("a built-ins struct is only ever used through translate_io_interface_to_ir, never interned as an ordinary type")
+            }
+        }
+    }
+}
+
 impl<'g> GenericSPIRVType<'g> for StructType<'g> {
     fn get_ir_type_with_state(
         &self,
-        _state: &mut GetIrTypeState<'g>,
+        state: &mut GetIrTypeState<'g>,
     ) -> TranslationResult<Option<Interned<'g, shader_compiler_ir::Type<'g>>>> {
-        todo!()
+        if let Some(cached) = state.get_struct_type(self.id) {
+            return Ok(cached);
+        }
+        let ir_type = self.get_ir_type_uncached(state)?;
+        state.insert_struct_type(self.id, ir_type);
+        Ok(ir_type)
     }
     fn get_relaxed_precision_type(&self) -> Option<SPIRVType<'g>> {
         None
     }
     fn get_alignment<I: FnOnce() -> spirv_parser::Instruction>(
         &self,
-        _target_properties: Interned<'g, TargetProperties>,
-        _global_state: &'g GlobalState<'g>,
+        target_properties: Interned<'g, TargetProperties>,
+        global_state: &'g GlobalState<'g>,
         _type_id: spirv_parser::IdRef,
         _instruction: I,
     ) -> TranslationResult<Alignment> {
-        todo!()
+        // a struct's alignment is the max over its members' alignments --
+        // each member gets its own freshly-built instruction closure
+        // (pointing at this struct's own declaration) rather than reusing
+        // the caller's `FnOnce`, which can only be invoked once
+        let mut alignment_in_bytes = 1;
+        for member in &self.members {
+            let member_alignment = member.member_type.get_alignment(
+                target_properties,
+                global_state,
+                member.member_type_id,
+                || self.get_struct_instruction().into(),
+            )?;
+            alignment_in_bytes = alignment_in_bytes.max(member_alignment.get());
+        }
+        if let StructKind::Block {
+            is_buffer_block: false,
+        } = self.kind
+        {
+            // std140's "extended alignment": round the base alignment up to
+            // a multiple of vec4 (16 bytes). std430 buffer blocks and
+            // generic (non-block) structs keep the unrounded base
+            // alignment.
+            alignment_in_bytes = (alignment_in_bytes + 15) / 16 * 16;
+        }
+        // TODO: consult `target_properties` for a platform-specific minimum
+        // struct alignment once one is plumbed through; none is today.
+        Ok(Alignment::new(alignment_in_bytes))
     }
     fn translate_io_interface_to_ir(
         &self,
@@ -211,7 +468,87 @@ impl<'g> GenericSPIRVType<'g> for StructType<'g> {
                     ir: IOInterfaceIR::UserInterfaceBlockMembers(members),
                 })
             }
-            StructKind::BuiltIns => todo!(),
+            StructKind::BuiltIns => {
+                // built-in members don't occupy user Locations at all, so
+                // start_location/first_location_after just pass through
+                // unchanged, and byte_offset/size_in_bytes (which describe
+                // a user-visible location's byte layout) aren't meaningful
+                // here
+                let mut variables = Vec::with_capacity(self.members.len());
+                for (member_index, member) in self.members.iter().enumerate() {
+                    let built_in = member.built_in.ok_or_else(|| {
+                        MissingBuiltInDecorationOnBuiltInsStructMember {
+                            type_id,
+                            member_index: member_index as u32,
+                        }
+                    })?;
+                    if let Some(location) = member.variable_or_struct_member.location {
+                        return Err(MemberDecorationNotAllowed {
+                            decoration: DecorationLocation { location }.into(),
+                            member_index: member_index as u32,
+                            instruction: self.get_struct_instruction().into(),
+                        }
+                        .into());
+                    }
+                    if let Some(component) =
+                        member.memory_object_declaration_or_struct_member.component
+                    {
+                        return Err(InvalidComponentDecorationOnVariableOrStructMember {
+                            type_id: member.member_type_id,
+                            component,
+                        }
+                        .into());
+                    }
+                    let IOInterfaceIRResult { ir, .. } =
+                        member.member_type.translate_io_interface_to_ir(
+                            global_state,
+                            member.member_type_id,
+                            None,
+                            None,
+                        )?;
+                    let member_type = match ir {
+                        IOInterfaceIR::IRType(v) => v,
+                        IOInterfaceIR::UserInterfaceBlockMembers(_)
+                        | IOInterfaceIR::BuiltInInterfaceVariables(_) => {
+                            return Err(BlockStructTypeNotAllowedAsMemberOfNonBlockStruct {
+                                member_type_id: member.member_type_id,
+                                outer_type_id: type_id,
+                            }
+                            .into())
+                        }
+                    };
+                    variables.push((built_in, member_type));
+                }
+                Ok(IOInterfaceIRResult {
+                    byte_offset: 0,
+                    size_in_bytes: 0,
+                    first_location_after: start_location,
+                    ir: IOInterfaceIR::BuiltInInterfaceVariables(variables),
+                })
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::runtime_array_stride;
+    use shader_compiler_ir::{Alignment, GlobalState, IntegerType, Internable};
+
+    #[test]
+    fn runtime_array_stride_rounds_element_size_up_to_its_alignment() {
+        let global_state = GlobalState::new();
+        let global_state = &global_state;
+        let int32 = IntegerType::Int32.intern(global_state);
+        assert_eq!(
+            runtime_array_stride(global_state, int32, Alignment::new(4)),
+            4
+        );
+        // a 4-byte element padded out to a 16-byte alignment (e.g. a block
+        // member following std140's vec4 rounding) has a 16-byte stride
+        assert_eq!(
+            runtime_array_stride(global_state, int32, Alignment::new(16)),
+            16
+        );
+    }
+}
@@ -0,0 +1,265 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! generates `shader-compiler-ir/src/generated_instructions.rs` from
+//! [[`table::INSTRUCTIONS`]]. Invoked as `cargo xtask codegen`; pass
+//! `--check` to instead regenerate into memory and fail (without touching
+//! the committed file) if it would differ, for use in CI.
+
+pub mod spirv_grammar;
+pub mod table;
+
+use table::{InstructionSpec, OperandSpec, ResultsSpec, TargetSpec, INSTRUCTIONS};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const GENERATED_FILE_PATH: &str = "shader-compiler-ir/src/generated_instructions.rs";
+const HEADER: &str = "\
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+// @generated by `cargo xtask codegen` from xtask/src/codegen/table.rs.
+// Do not edit by hand -- edit the table and regenerate instead.
+";
+
+/// render the full contents of `generated_instructions.rs` from the table
+pub fn render(instructions: &[InstructionSpec]) -> String {
+    let mut out = String::new();
+    out.push_str(HEADER);
+    out.push('\n');
+    for instruction in instructions {
+        render_to_text_arm(&mut out, instruction);
+        render_from_text_arm(&mut out, instruction);
+    }
+    out.push_str("#[cfg(test)]\nmod generated_round_trip_tests {\n");
+    out.push_str("    use super::*;\n\n");
+    for instruction in instructions {
+        render_round_trip_test(&mut out, instruction);
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn render_to_text_arm(out: &mut String, instruction: &InstructionSpec) {
+    let InstructionSpec {
+        mnemonic,
+        struct_name,
+        operands,
+        results,
+        target,
+    } = instruction;
+    writeln!(out, "/// `ToText` body generated for `{}`", struct_name).unwrap();
+    writeln!(
+        out,
+        "pub fn to_text_{mnemonic}<'g>(value: &{struct_name}<'g>, state: &mut ToTextState<'g, '_>) -> std::fmt::Result {{",
+        mnemonic = mnemonic,
+        struct_name = struct_name
+    )
+    .unwrap();
+    writeln!(out, "    write!(state, \"{} \")?;", mnemonic).unwrap();
+    if let Some(target) = target {
+        let field = match target {
+            TargetSpec::Block => "block",
+            TargetSpec::Loop => "target_loop",
+        };
+        writeln!(out, "    value.{}.to_text(state)?;", field).unwrap();
+    }
+    for operand in *operands {
+        let name = match operand {
+            OperandSpec::Value { name, .. } | OperandSpec::ValueList { name } => name,
+        };
+        writeln!(out, "    value.{}.to_text(state)?;", name).unwrap();
+    }
+    if let ResultsSpec::Single { name, .. } = results {
+        out.push_str("    write!(state, \" -> \")?;\n");
+        writeln!(out, "    value.{}.to_text(state)?;", name).unwrap();
+    }
+    out.push_str("    Ok(())\n}\n\n");
+}
+
+fn render_from_text_arm(out: &mut String, instruction: &InstructionSpec) {
+    let InstructionSpec {
+        mnemonic,
+        struct_name,
+        operands,
+        results,
+        target,
+    } = instruction;
+    writeln!(
+        out,
+        "/// `FromText` body generated for `{}`, called once the `{}` mnemonic is peeked",
+        struct_name, mnemonic
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "pub fn from_text_{mnemonic}<'g>(state: &mut FromTextState<'g, '_>) -> Result<{struct_name}<'g>, FromTextError> {{",
+        mnemonic = mnemonic,
+        struct_name = struct_name
+    )
+    .unwrap();
+    if let Some(target) = target {
+        let (field, ty) = match target {
+            TargetSpec::Block => ("block", "BlockRef"),
+            TargetSpec::Loop => ("target_loop", "LoopRef"),
+        };
+        writeln!(out, "    let {} = {}::from_text(state)?;", field, ty).unwrap();
+    }
+    for operand in *operands {
+        let name = match operand {
+            OperandSpec::Value { name, .. } => {
+                writeln!(out, "    let {} = ValueUse::from_text(state)?;", name).unwrap();
+                name
+            }
+            OperandSpec::ValueList { name } => {
+                writeln!(
+                    out,
+                    "    let {} = Vec::<ValueUse>::from_text(state)?;",
+                    name
+                )
+                .unwrap();
+                name
+            }
+        };
+        let _ = name;
+    }
+    if let ResultsSpec::Single { name, .. } = results {
+        out.push_str(
+            "    state.parse_punct_token_or_error(Punctuation::Arrow, \"missing arrow: '->'\")?;\n",
+        );
+        writeln!(
+            out,
+            "    let {} = ValueDefinition::from_text(state)?;",
+            name
+        )
+        .unwrap();
+    }
+    out.push_str("    Ok(");
+    write!(out, "{} {{ ", struct_name).unwrap();
+    if let Some(target) = target {
+        let field = match target {
+            TargetSpec::Block => "block",
+            TargetSpec::Loop => "target_loop",
+        };
+        write!(out, "{}, ", field).unwrap();
+    }
+    for operand in *operands {
+        let name = match operand {
+            OperandSpec::Value { name, .. } | OperandSpec::ValueList { name } => name,
+        };
+        write!(out, "{}, ", name).unwrap();
+    }
+    if let ResultsSpec::Single { name, .. } = results {
+        write!(out, "{}, ", name).unwrap();
+    }
+    out.push_str("})\n}\n\n");
+}
+
+/// builds one instruction instance, prints it, reparses it, and checks the
+/// reparsed instance prints back to the same text -- a real round trip
+/// through both generated functions, not just a syntactic sanity check.
+fn render_round_trip_test(out: &mut String, instruction: &InstructionSpec) {
+    let InstructionSpec {
+        mnemonic,
+        struct_name,
+        operands,
+        results,
+        target,
+    } = instruction;
+    writeln!(out, "    #[test]").unwrap();
+    writeln!(
+        out,
+        "    fn generated_round_trip_{mnemonic}() {{",
+        mnemonic = mnemonic
+    )
+    .unwrap();
+    out.push_str("        let global_state = GlobalState::new();\n");
+    out.push_str("        let global_state = &global_state;\n");
+    if let Some(target) = target {
+        match target {
+            TargetSpec::Block => {
+                out.push_str("        let target_block = Block::without_body(\"target\", Inhabited(vec![]), global_state);\n");
+                out.push_str("        let block = BlockRef::new(target_block.value());\n");
+            }
+            TargetSpec::Loop => {
+                out.push_str("        let target_block = Block::without_body(\"target\", Inhabited(vec![]), global_state);\n");
+                out.push_str("        let target_loop = Loop::new(\"target_loop\", vec![], vec![], target_block, global_state);\n");
+                out.push_str("        let target_loop = LoopRef::new(target_loop.value());\n");
+            }
+        }
+    }
+    for operand in *operands {
+        match operand {
+            OperandSpec::Value { name, test_value } => {
+                writeln!(
+                    out,
+                    "        let {name} = ValueUse::from_const({test_value}u32, \"\", global_state);",
+                    name = name,
+                    test_value = test_value
+                )
+                .unwrap();
+            }
+            OperandSpec::ValueList { name } => {
+                writeln!(
+                    out,
+                    "        let {name}: Vec<ValueUse> = vec![];",
+                    name = name
+                )
+                .unwrap();
+            }
+        }
+    }
+    if let ResultsSpec::Single { name, test_type } = results {
+        writeln!(
+            out,
+            "        let {name} = ValueDefinition::new({test_type}, \"{name}\", global_state);",
+            name = name,
+            test_type = test_type
+        )
+        .unwrap();
+    }
+    out.push_str("        let value = ");
+    write!(out, "{} {{ ", struct_name).unwrap();
+    if let Some(target) = target {
+        let field = match target {
+            TargetSpec::Block => "block",
+            TargetSpec::Loop => "target_loop",
+        };
+        write!(out, "{}, ", field).unwrap();
+    }
+    for operand in *operands {
+        let name = match operand {
+            OperandSpec::Value { name, .. } | OperandSpec::ValueList { name } => name,
+        };
+        write!(out, "{}, ", name).unwrap();
+    }
+    if let ResultsSpec::Single { name, .. } = results {
+        write!(out, "{}, ", name).unwrap();
+    }
+    out.push_str("};\n");
+    out.push_str("        let text = value.display().to_string();\n");
+    writeln!(
+        out,
+        "        let parsed = {struct_name}::parse(\"\", &text, global_state).unwrap();",
+        struct_name = struct_name
+    )
+    .unwrap();
+    out.push_str("        assert_eq!(text, parsed.display().to_string());\n");
+    out.push_str("    }\n\n");
+}
+
+/// regenerate `generated_instructions.rs` in place
+pub fn write(repo_root: &Path) -> io::Result<()> {
+    let rendered = render(INSTRUCTIONS);
+    fs::write(repo_root.join(GENERATED_FILE_PATH), rendered)
+}
+
+/// regenerate into memory and compare against the committed file, without
+/// writing anything. Returns `Ok(true)` if they match.
+pub fn check(repo_root: &Path) -> io::Result<bool> {
+    let rendered = render(INSTRUCTIONS);
+    let committed = fs::read_to_string(repo_root.join(GENERATED_FILE_PATH))?;
+    Ok(rendered == committed)
+}
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! generates `shader-compiler-translate-spirv-to-ir`'s opcode name table,
+//! operand-count validation and shared unimplemented-opcode error path
+//! from the Khronos `spirv.core.grammar.json`. It does not generate the
+//! `ParseInstruction` dispatch itself: the `unimplemented_instruction!`
+//! line for each opcode without a handler is still written by hand in
+//! `unimplemented_instructions.rs`.
+//!
+//! Invoked as `cargo xtask codegen-spirv-dispatch [--check]`, mirroring
+//! `cargo xtask codegen` (see `super::table`): the committed generated
+//! file holds the opcode name table and the operand-count validator;
+//! handlers for individual opcodes are written by hand elsewhere and are
+//! unaffected by a grammar revision bump -- only the table and validator
+//! regenerate.
+
+use serde::Deserialize;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const GRAMMAR_FILE_PATH: &str = "shader-compiler-translate-spirv-to-ir/spirv.core.grammar.json";
+const GENERATED_FILE_PATH: &str =
+    "shader-compiler-translate-spirv-to-ir/src/parse/generated_dispatch.rs";
+const HEADER: &str = "\
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+// @generated by `cargo xtask codegen-spirv-dispatch` from spirv.core.grammar.json.
+// Do not edit by hand -- bump the grammar file and regenerate instead.
+";
+
+#[derive(Deserialize)]
+pub struct Grammar {
+    instructions: Vec<GrammarInstruction>,
+}
+
+#[derive(Deserialize)]
+struct GrammarInstruction {
+    opname: String,
+    opcode: u16,
+    #[serde(default)]
+    operands: Vec<GrammarOperand>,
+}
+
+#[derive(Deserialize)]
+struct GrammarOperand {
+    kind: String,
+    #[serde(default)]
+    quantifier: Option<String>,
+}
+
+impl GrammarOperand {
+    /// this operand is present in every well-formed encoding of the instruction
+    fn is_required(&self) -> bool {
+        self.quantifier.is_none()
+    }
+
+    /// `IdResultType`/`IdResult` are handled uniformly before operand
+    /// decoding even starts, so they're excluded from the operand-count
+    /// the generated validator checks.
+    fn is_result_or_result_type(&self) -> bool {
+        self.kind == "IdResultType" || self.kind == "IdResult"
+    }
+}
+
+/// render the full contents of `generated_dispatch.rs` from `grammar`
+pub fn render(grammar: &Grammar) -> String {
+    let mut out = String::new();
+    out.push_str(HEADER);
+    out.push('\n');
+    render_opcode_name_table(&mut out, grammar);
+    render_operand_shape_table(&mut out, grammar);
+    render_unimplemented_opcode_fallback(&mut out);
+    out
+}
+
+fn render_opcode_name_table(out: &mut String, grammar: &Grammar) {
+    out.push_str("/// the mnemonic the grammar gives an opcode, for diagnostics\n");
+    out.push_str("pub(crate) fn opcode_name(opcode: u16) -> &'static str {\n");
+    out.push_str("    match opcode {\n");
+    for instruction in &grammar.instructions {
+        writeln!(
+            out,
+            "        {} => \"{}\",",
+            instruction.opcode, instruction.opname
+        )
+        .unwrap();
+    }
+    out.push_str("        _ => \"<opcode not in spirv.core.grammar.json>\",\n");
+    out.push_str("    }\n}\n\n");
+}
+
+fn render_operand_shape_table(out: &mut String, grammar: &Grammar) {
+    out.push_str(
+        "/// the number of required operands (after `IdResultType`/`IdResult`, if present)\n",
+    );
+    out.push_str("/// an opcode's grammar entry declares, not counting optional/variadic tail operands\n");
+    out.push_str("pub(crate) fn min_operand_count(opcode: u16) -> usize {\n");
+    out.push_str("    match opcode {\n");
+    for instruction in &grammar.instructions {
+        let required_count = instruction
+            .operands
+            .iter()
+            .filter(|operand| !operand.is_result_or_result_type() && operand.is_required())
+            .count();
+        writeln!(out, "        {} => {},", instruction.opcode, required_count).unwrap();
+    }
+    out.push_str("        _ => 0,\n");
+    out.push_str("    }\n}\n\n");
+    out.push_str(
+        "/// checks `operand_count` against the grammar's required-operand count for `opcode`\n",
+    );
+    out.push_str("pub(crate) fn validate_operand_count(\n");
+    out.push_str("    opcode: u16,\n");
+    out.push_str("    operand_count: usize,\n");
+    out.push_str(") -> crate::TranslationResult<()> {\n");
+    out.push_str("    let min = min_operand_count(opcode);\n");
+    out.push_str("    if operand_count < min {\n");
+    out.push_str("        return Err(crate::errors::InvalidOperandCount {\n");
+    out.push_str("            opcode,\n");
+    out.push_str("            min_operand_count: min,\n");
+    out.push_str("            operand_count,\n");
+    out.push_str("        }\n");
+    out.push_str("        .into());\n");
+    out.push_str("    }\n");
+    out.push_str("    Ok(())\n}\n\n");
+}
+
+fn render_unimplemented_opcode_fallback(out: &mut String) {
+    out.push_str(
+        "/// the single shared error path every opcode without a hand-written handler falls back to,\n",
+    );
+    out.push_str(
+        "/// replacing what would otherwise be one `todo!()` call site per unimplemented opcode\n",
+    );
+    out.push_str(
+        "pub(crate) fn unimplemented_opcode<T>(opcode: u16) -> crate::TranslationResult<T> {\n",
+    );
+    out.push_str("    Err(crate::errors::UnimplementedInstruction { opcode }.into())\n}\n");
+}
+
+/// regenerate `generated_dispatch.rs` in place
+pub fn write(repo_root: &Path) -> io::Result<()> {
+    let grammar = read_grammar(repo_root)?;
+    let rendered = render(&grammar);
+    fs::write(repo_root.join(GENERATED_FILE_PATH), rendered)
+}
+
+/// regenerate into memory and compare against the committed file, without writing anything
+pub fn check(repo_root: &Path) -> io::Result<bool> {
+    let grammar = read_grammar(repo_root)?;
+    let rendered = render(&grammar);
+    let committed = fs::read_to_string(repo_root.join(GENERATED_FILE_PATH))?;
+    Ok(rendered == committed)
+}
+
+fn read_grammar(repo_root: &Path) -> io::Result<Grammar> {
+    let text = fs::read_to_string(repo_root.join(GRAMMAR_FILE_PATH))?;
+    serde_json::from_str(&text).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! the single declarative table that drives instruction codegen.
+//!
+//! Adding an opcode is a one-line edit to `INSTRUCTIONS`; `cargo xtask codegen`
+//! turns that into the parser match arm, the to-text emitter, and a
+//! round-trip unit test, so the three can't drift out of sync with each other.
+
+/// the shape of one operand read from the instruction's argument list
+pub enum OperandSpec {
+    /// a single `ValueUse`
+    Value {
+        /// the field name in the instruction struct
+        name: &'static str,
+        /// the constant the generated round-trip test builds this operand from
+        test_value: u32,
+    },
+    /// a `Vec<ValueUse>`
+    ValueList {
+        /// the field name in the instruction struct
+        name: &'static str,
+    },
+}
+
+/// how an instruction's results are declared
+pub enum ResultsSpec {
+    /// the instruction produces no results and is a terminator (`Uninhabited`)
+    Uninhabited,
+    /// the instruction produces exactly one named, typed result
+    Single {
+        /// the result field name
+        name: &'static str,
+        /// the type expression the generated round-trip test builds this
+        /// result's `ValueDefinition` from
+        test_type: &'static str,
+    },
+    /// the instruction's results are those of a nested region it owns (e.g. `Loop`)
+    InheritedFromBody,
+}
+
+/// whether the instruction references an enclosing block or loop by name
+pub enum TargetSpec {
+    /// references a `BlockRef`
+    Block,
+    /// references a `LoopRef`
+    Loop,
+}
+
+/// one row of the instruction table
+pub struct InstructionSpec {
+    /// the keyword used in the text grammar, e.g. `"break"`
+    pub mnemonic: &'static str,
+    /// the Rust struct this instruction decodes to
+    pub struct_name: &'static str,
+    /// the operands parsed after the mnemonic (and target, if any)
+    pub operands: &'static [OperandSpec],
+    /// how this instruction's results are declared
+    pub results: ResultsSpec,
+    /// an in-scope block/loop name parsed right after the mnemonic, if any
+    pub target: Option<TargetSpec>,
+}
+
+// note: there's deliberately no `has_location` field here. `@ "file":line:col`
+// is parsed/printed once, generically, by the `Instruction` wrapper around any
+// payload (see `Instruction::with_location`/`without_location`), not per
+// instruction struct, so the table has nothing to say about it.
+
+/// the instruction table. This is the only thing that needs to change to add,
+/// remove, or reshape an opcode -- see module docs.
+pub const INSTRUCTIONS: &[InstructionSpec] = &[
+    InstructionSpec {
+        mnemonic: "add",
+        struct_name: "BinaryALUInstruction",
+        operands: &[
+            OperandSpec::Value {
+                name: "lhs",
+                test_value: 1,
+            },
+            OperandSpec::Value {
+                name: "rhs",
+                test_value: 2,
+            },
+        ],
+        results: ResultsSpec::Single {
+            name: "result",
+            test_type: "IntegerType::Int32",
+        },
+        target: None,
+    },
+    InstructionSpec {
+        mnemonic: "branch",
+        struct_name: "BranchInstruction",
+        operands: &[OperandSpec::Value {
+            name: "variable",
+            test_value: 1,
+        }],
+        results: ResultsSpec::Uninhabited,
+        target: None,
+    },
+    InstructionSpec {
+        mnemonic: "break",
+        struct_name: "BreakBlock",
+        operands: &[OperandSpec::ValueList {
+            name: "block_results",
+        }],
+        results: ResultsSpec::Uninhabited,
+        target: Some(TargetSpec::Block),
+    },
+    InstructionSpec {
+        mnemonic: "continue",
+        struct_name: "ContinueLoop",
+        operands: &[OperandSpec::ValueList {
+            name: "loop_arguments",
+        }],
+        results: ResultsSpec::Uninhabited,
+        target: Some(TargetSpec::Loop),
+    },
+    InstructionSpec {
+        mnemonic: "loop",
+        struct_name: "Loop",
+        operands: &[OperandSpec::ValueList { name: "arguments" }],
+        results: ResultsSpec::InheritedFromBody,
+        target: None,
+    },
+];
@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: LGPL-2.1-or-later
+// See Notices.txt for copyright information
+
+//! repo-local developer tasks, run as `cargo xtask <task>`.
+
+mod codegen;
+
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let repo_root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is always a child of the repo root");
+    match args.next().as_deref() {
+        Some("codegen") => {
+            if args.any(|arg| arg == "--check") {
+                match codegen::check(repo_root) {
+                    Ok(true) => ExitCode::SUCCESS,
+                    Ok(false) => {
+                        eprintln!(
+                            "generated_instructions.rs is out of date; run `cargo xtask codegen`"
+                        );
+                        ExitCode::FAILURE
+                    }
+                    Err(error) => {
+                        eprintln!("codegen --check failed: {}", error);
+                        ExitCode::FAILURE
+                    }
+                }
+            } else {
+                match codegen::write(repo_root) {
+                    Ok(()) => ExitCode::SUCCESS,
+                    Err(error) => {
+                        eprintln!("codegen failed: {}", error);
+                        ExitCode::FAILURE
+                    }
+                }
+            }
+        }
+        Some("codegen-spirv-dispatch") => {
+            if args.any(|arg| arg == "--check") {
+                match codegen::spirv_grammar::check(repo_root) {
+                    Ok(true) => ExitCode::SUCCESS,
+                    Ok(false) => {
+                        eprintln!(
+                            "generated_dispatch.rs is out of date; run `cargo xtask codegen-spirv-dispatch`"
+                        );
+                        ExitCode::FAILURE
+                    }
+                    Err(error) => {
+                        eprintln!("codegen-spirv-dispatch --check failed: {}", error);
+                        ExitCode::FAILURE
+                    }
+                }
+            } else {
+                match codegen::spirv_grammar::write(repo_root) {
+                    Ok(()) => ExitCode::SUCCESS,
+                    Err(error) => {
+                        eprintln!("codegen-spirv-dispatch failed: {}", error);
+                        ExitCode::FAILURE
+                    }
+                }
+            }
+        }
+        Some(other) => {
+            eprintln!("unknown xtask: {}", other);
+            ExitCode::FAILURE
+        }
+        None => {
+            eprintln!("usage: cargo xtask <codegen|codegen-spirv-dispatch> [--check]");
+            ExitCode::FAILURE
+        }
+    }
+}